@@ -0,0 +1,65 @@
+//! Benchmark du coût d'un `dealloc` en fonction du nombre de slabs du cache.
+//!
+//! Deux colonnes :
+//! - `masqué` : le slab propriétaire est retrouvé en masquant l'adresse
+//!   (header en début de page), le temps par libération doit rester constant ;
+//! - `parcours` : référence, le propriétaire est d'abord cherché en
+//!   parcourant les listes du cache (`Cache::owns`), comme avant le masquage.
+//!
+//! `cargo bench --bench dealloc`
+
+use core::ptr::NonNull;
+use std::hint::black_box;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use allocator::page_provider::StaticPageProvider;
+use allocator::Cache;
+
+const N_PAGES: usize = 1100;
+const OBJ_SIZE: usize = 64;
+const ROUNDS: usize = 20;
+
+// Le pool (4 MiB) est trop gros pour la stack : on le place dans un static.
+static PROVIDER: Mutex<StaticPageProvider<N_PAGES>> = Mutex::new(StaticPageProvider::new());
+
+fn bench_free(slabs: usize, walk: bool) -> f64 {
+    let mut provider = PROVIDER.lock().unwrap();
+    let mut cache = Cache::new(OBJ_SIZE, OBJ_SIZE);
+
+    // Nombre d'objets par slab (1 page - header).
+    let per_slab = (4096 - OBJ_SIZE) / OBJ_SIZE;
+    let count = slabs * per_slab;
+    let mut ptrs: Vec<NonNull<u8>> = Vec::with_capacity(count);
+
+    let mut total_ns = 0u128;
+    for _ in 0..ROUNDS {
+        for _ in 0..count {
+            ptrs.push(cache.alloc(&mut *provider).expect("OOM"));
+        }
+
+        // Les plus anciens slabs sont en queue de liste : on libère dans
+        // l'ordre d'allocation, le pire cas pour un parcours linéaire.
+        let start = Instant::now();
+        for &p in &ptrs {
+            if walk {
+                assert!(cache.owns(black_box(p)));
+            }
+            unsafe { cache.dealloc(black_box(p), &mut *provider) };
+        }
+        total_ns += start.elapsed().as_nanos();
+        ptrs.clear();
+    }
+
+    // Pages rendues au pool pour la mesure suivante.
+    cache.set_empty_limit(0);
+    cache.shrink(&mut *provider);
+    total_ns as f64 / (ROUNDS * count) as f64
+}
+
+fn main() {
+    println!("{:>8} {:>12} {:>12}", "slabs", "masqué ns", "parcours ns");
+    for slabs in [1usize, 16, 128, 512, 1024] {
+        println!("{:>8} {:>12.1} {:>12.1}", slabs, bench_free(slabs, false), bench_free(slabs, true));
+    }
+}
@@ -3,13 +3,11 @@
 use libfuzzer_sys::fuzz_target;
 use core::alloc::Layout;
 
-use allocator::SlabAllocator;
+use allocator::{SlabAllocator, SIZE_CLASSES};
 
 // On active le provider std via feature
 use allocator::page_provider::TestPageProvider;
 
-const SIZE_CLASSES: &[usize] = &[8, 16, 32, 64, 128, 256, 512, 1024, 2048];
-
 #[derive(Clone, Copy)]
 struct Live {
     ptr: *mut u8,
@@ -26,7 +24,7 @@ fuzz_target!(|data: &[u8]| {
 
     let mut i = 0usize;
     while i < data.len() {
-        let op = data[i] % 3; // 0 alloc, 1 free, 2 realloc-like
+        let op = data[i] % 3; // 0 alloc, 1 free, 2 realloc
         i += 1;
 
         // index dans le tableau live
@@ -50,10 +48,8 @@ fuzz_target!(|data: &[u8]| {
                 i += 1;
                 let align = 1usize << (align_pow as usize);
 
+                // align > size : routé vers une classe plus grande
                 let size = SIZE_CLASSES[sz_idx];
-                if align > size {
-                    continue;
-                }
 
                 let layout = match Layout::from_size_align(size, align) {
                     Ok(l) => l,
@@ -77,25 +73,35 @@ fuzz_target!(|data: &[u8]| {
                 }
             }
 
-            // REALLOC-LIKE (free + alloc autre size)
+            // REALLOC
             _ => {
-                if let Some(l) = live[slot].take() {
-                    unsafe { a.dealloc(l.ptr, l.layout) };
-                }
-
                 if i >= data.len() { break; }
                 let sz_idx = (data[i] as usize) % SIZE_CLASSES.len();
                 i += 1;
 
-                let size = SIZE_CLASSES[sz_idx];
-                let layout = match Layout::from_size_align(size, 8) {
-                    Ok(l) => l,
-                    Err(_) => continue,
-                };
+                // Tailles au-delà de la dernière classe : chemin multi-pages.
+                if i >= data.len() { break; }
+                let new_size = SIZE_CLASSES[sz_idx] * (1 + (data[i] % 4) as usize);
+                i += 1;
 
-                let p = a.alloc(layout);
-                if !p.is_null() {
-                    live[slot] = Some(Live { ptr: p, layout });
+                match live[slot] {
+                    Some(l) => {
+                        let p = unsafe { a.realloc(l.ptr, l.layout, new_size) };
+                        if !p.is_null() {
+                            let layout = Layout::from_size_align(new_size, l.layout.align()).unwrap();
+                            live[slot] = Some(Live { ptr: p, layout });
+                        }
+                    }
+                    None => {
+                        let layout = match Layout::from_size_align(new_size, 8) {
+                            Ok(l) => l,
+                            Err(_) => continue,
+                        };
+                        let p = a.alloc(layout);
+                        if !p.is_null() {
+                            live[slot] = Some(Live { ptr: p, layout });
+                        }
+                    }
                 }
             }
         }
@@ -1,65 +1,159 @@
 use core::alloc::Layout;
 use core::ptr::NonNull;
 
-use crate::cache::Cache;
-use crate::page_provider::PageProvider;
+use crate::cache::{Cache, ObjectFn};
+use crate::error::AllocError;
+use crate::large::LargeAllocs;
+use crate::named_cache::{CacheError, CacheFlags, CacheHandle, NamedCaches};
+use crate::page_provider::{PageProvider, PAGE_SIZE};
+use crate::slab::{FreelistRandom, Slab};
+use crate::size_class::{SizeClasses, DEFAULT_CLASS_SIZES, DEFAULT_SIZE_CLASSES, FINE_SIZE_CLASSES};
 
-pub const SIZE_CLASSES: [usize; 9] = [8, 16, 32, 64, 128, 256, 512, 1024, 2048];
+/// Table par défaut (8..2048, puissances de 2).
+pub const SIZE_CLASSES: [usize; 9] = DEFAULT_CLASS_SIZES;
 
-pub struct SlabAllocator<P: PageProvider> {
+/// Destination d'un `Layout` : une size class ou le chemin multi-pages.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Route {
+    Class(usize),
+    Large,
+}
+
+/// Choisit la destination d'un layout.
+///
+/// Plus petite classe qui satisfait à la fois la taille et l'alignement ;
+/// sinon on passe aux pages (alignées sur PAGE_SIZE). Seul un
+/// alignement > PAGE_SIZE est impossible.
+#[inline]
+pub(crate) fn route<const N: usize>(classes: &SizeClasses<N>, layout: Layout) -> Result<Route, AllocError> {
+    let size = layout.size().max(1);
+    let align = layout.align();
+
+    if let Some(idx) = classes.index_for_layout(size, align) {
+        return Ok(Route::Class(idx));
+    }
+
+    if align <= PAGE_SIZE {
+        Ok(Route::Large)
+    } else {
+        Err(AllocError::AlignmentTooLarge { align })
+    }
+}
+
+/// Allocateur slab : un `Cache` par size class + chemin multi-pages.
+///
+/// `N` est le nombre de classes ; la table elle-même est fournie à la
+/// construction (`with_classes`), `new` utilise la table par défaut.
+pub struct SlabAllocator<P: PageProvider, const N: usize = 9> {
     provider: P,
-    caches: [Cache; 9],
+    classes: SizeClasses<N>,
+    caches: [Cache; N],
+    large: LargeAllocs,
+    named: NamedCaches,
 }
 
+// SAFETY: les slabs référencés par les caches sont des pages possédées
+// exclusivement par cet allocateur (obtenues via `provider`). Déplacer
+// l'allocateur vers un autre thread déplace donc aussi la propriété de ces pages.
+unsafe impl<P: PageProvider + Send, const N: usize> Send for SlabAllocator<P, N> {}
+
 impl<P: PageProvider> SlabAllocator<P> {
-    pub fn new(provider: P) -> Self {
-        let caches = [
-            Cache::new(8, 8),
-            Cache::new(16, 16),
-            Cache::new(32, 32),
-            Cache::new(64, 64),
-            Cache::new(128, 128),
-            Cache::new(256, 256),
-            Cache::new(512, 512),
-            Cache::new(1024, 1024),
-            Cache::new(2048, 2048),
-        ];
-
-        Self { provider, caches }
+    /// Allocateur avec la table par défaut (8..2048).
+    pub const fn new(provider: P) -> Self {
+        Self::with_classes(provider, DEFAULT_SIZE_CLASSES)
     }
+}
 
-    #[inline]
-    fn class_index(size: usize) -> Option<usize> {
-        SIZE_CLASSES.iter().position(|&c| c >= size)
+impl<P: PageProvider> SlabAllocator<P, 26> {
+    /// Allocateur avec la table fine (`FINE_CLASS_SIZES`, 26 classes) :
+    /// moins de fragmentation interne, un peu plus de caches.
+    pub const fn new_fine(provider: P) -> Self {
+        Self::with_classes(provider, FINE_SIZE_CLASSES)
     }
+}
 
-    #[inline]
-    fn pick_index(layout: Layout) -> Option<usize> {
-        let size = layout.size().max(1);
-        let align = layout.align();
+impl<P: PageProvider, const N: usize> SlabAllocator<P, N> {
+    /// Allocateur avec une table de size classes personnalisée.
+    pub const fn with_classes(provider: P, classes: SizeClasses<N>) -> Self {
+        let mut caches = [const { Cache::new(0, 1) }; N];
+        let mut i = 0;
+        while i < N {
+            caches[i] = Cache::new(classes.size(i), classes.align(i)).with_tag(i as u16);
+            i += 1;
+        }
 
-        let idx = Self::class_index(size)?;
+        Self {
+            provider,
+            classes,
+            caches,
+            large: LargeAllocs::new(),
+            named: NamedCaches::new(),
+        }
+    }
 
-        // règle minimale: align <= sizeclass
-        if align > SIZE_CLASSES[idx] {
-            return None;
+    /// Mélange la freelist initiale de chaque nouveau slab (voir
+    /// `Cache::with_random_freelist`), avec une graine distincte par size class.
+    ///
+    /// Les caches nommés ne sont mélangés que s'ils sont créés avec
+    /// `CacheFlags::RANDOM_FREELIST` ; ils tirent alors leur générateur de
+    /// `random`. La graine doit être imprévisible (voir
+    /// `FreelistRandom::with_seed`).
+    pub const fn with_random_freelist(mut self, random: FreelistRandom) -> Self {
+        let mut i = 0;
+        while i < N {
+            let cache = core::mem::replace(&mut self.caches[i], Cache::new(0, 1));
+            self.caches[i] = cache.with_random_freelist(random.offset(i as u64));
+            i += 1;
         }
+        self.named.set_random_freelist(random.offset(N as u64));
+        self
+    }
+
+    /// Table de size classes utilisée.
+    pub fn classes(&self) -> &SizeClasses<N> {
+        &self.classes
+    }
 
-        Some(idx)
+    /// Destination d'un layout (voir `route`).
+    #[inline]
+    fn route(&self, layout: Layout) -> Result<Route, AllocError> {
+        route(&self.classes, layout)
     }
 
+    /// Alloue un bloc pour `layout` ; null en cas d'échec (voir `try_alloc`).
     pub fn alloc(&mut self, layout: Layout) -> *mut u8 {
-        let Some(idx) = Self::pick_index(layout) else {
-            return core::ptr::null_mut();
-        };
+        self.try_alloc(layout).map_or(core::ptr::null_mut(), NonNull::as_ptr)
+    }
+
+    /// Comme `alloc`, avec la raison de l'échec.
+    pub fn try_alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        let route = self.route(layout)?;
 
         // Emprunts séparés => plus de E0499
         let provider = &mut self.provider;
-        let cache = &mut self.caches[idx];
+        match route {
+            Route::Class(idx) => self.caches[idx].try_alloc(provider),
+            Route::Large => self.large.alloc(layout.size(), provider),
+        }
+    }
 
-        match cache.alloc(provider) {
-            Some(p) => p.as_ptr(),
-            None => core::ptr::null_mut(),
+    /// Comme `alloc`, mais le bloc est rempli de zéros.
+    ///
+    /// Seuls les objets recyclés sont effacés : un objet jamais distribué
+    /// depuis que sa page est arrivée à zéro (`PageProvider::ZEROES_PAGES`)
+    /// l'est déjà.
+    pub fn alloc_zeroed(&mut self, layout: Layout) -> *mut u8 {
+        self.try_alloc_zeroed(layout).map_or(core::ptr::null_mut(), NonNull::as_ptr)
+    }
+
+    /// Comme `alloc_zeroed`, avec la raison de l'échec.
+    pub fn try_alloc_zeroed(&mut self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        let route = self.route(layout)?;
+
+        let provider = &mut self.provider;
+        match route {
+            Route::Class(idx) => self.caches[idx].try_alloc_zeroed(provider),
+            Route::Large => self.large.alloc_zeroed(layout.size(), provider),
         }
     }
 
@@ -68,25 +162,334 @@ impl<P: PageProvider> SlabAllocator<P> {
     /// - `layout` doit être identique à celui utilisé lors de l'allocation (même size/align).
     /// - pas de double-free.
     pub unsafe fn dealloc(&mut self, ptr: *mut u8, layout: Layout) {
+        let Some(nn) = NonNull::new(ptr) else { return };
+        // SAFETY: préconditions identiques.
+        let res = unsafe { self.try_dealloc(nn, layout) };
+        debug_assert!(res.is_ok(), "dealloc: {res:?}");
+    }
+
+    /// Comme `dealloc`, mais signale un layout impossible ou un pointeur qui
+    /// ne correspond pas à la destination de `layout` (rien n'est libéré).
+    ///
+    /// # Safety
+    /// - `ptr` doit provenir d’un `alloc` de CET allocator, non encore libéré.
+    /// - un layout erroné est détecté tant que la page de `ptr` est lisible ;
+    ///   il ne l'est pas s'il route vers une autre classe de même pas.
+    pub unsafe fn try_dealloc(&mut self, ptr: NonNull<u8>, layout: Layout) -> Result<(), AllocError> {
+        match self.route(layout)? {
+            Route::Class(idx) => {
+                let cache = &mut self.caches[idx];
+                // SAFETY:
+                // - ptr provient d’un alloc de CET allocator (précondition)
+                // - les pages du cache viennent de `self.provider`
+                // - pas de double free (précondition)
+                unsafe { cache.try_dealloc(ptr, &mut self.provider) }
+            }
+            Route::Large => {
+                // SAFETY: si `ptr` est une grosse allocation, elle n'est pas déjà libérée.
+                let found = unsafe { self.large.dealloc(ptr, &mut self.provider) };
+                found.then_some(()).ok_or(AllocError::UnknownLargeAllocation)
+            }
+        }
+    }
+
+    /// Libère `ptr` sans connaître son layout (`free` C).
+    ///
+    /// La classe est retrouvée dans le header de la page (étiquette du
+    /// cache) ; un pointeur aligné sur PAGE_SIZE ne peut être qu'une grosse
+    /// allocation (les objets d'un slab suivent son header).
+    ///
+    /// # Safety
+    /// - `ptr` doit provenir d'un `alloc` de CET allocator, non encore libéré.
+    pub unsafe fn free(&mut self, ptr: *mut u8) {
+        let Some(nn) = NonNull::new(ptr) else { return };
+        // SAFETY: préconditions identiques.
+        let res = unsafe { self.try_free(nn) };
+        debug_assert!(res.is_ok(), "free: {res:?}");
+    }
+
+    /// Comme `free`, avec la raison de l'échec (rien n'est libéré).
+    ///
+    /// # Safety
+    /// Voir `free`.
+    pub unsafe fn try_free(&mut self, ptr: NonNull<u8>) -> Result<(), AllocError> {
+        if Self::is_page_aligned(ptr) {
+            // SAFETY: si `ptr` est une grosse allocation, elle n'est pas déjà libérée.
+            let found = unsafe { self.large.dealloc(ptr, &mut self.provider) };
+            return found.then_some(()).ok_or(AllocError::UnknownLargeAllocation);
+        }
+
+        // SAFETY: `ptr` vient de cet allocateur => sa page est lisible.
+        let idx = unsafe { self.class_of(ptr) }?;
+        // SAFETY: objet de ce cache (étiquette et pas vérifiés), pas de double free.
+        unsafe { self.caches[idx].try_dealloc(ptr, &mut self.provider) }
+    }
+
+    /// Taille réellement utilisable du bloc `ptr` (taille de sa classe, ou
+    /// pages entières pour une grosse allocation). None si le bloc n'est pas
+    /// reconnu.
+    ///
+    /// # Safety
+    /// - `ptr` doit provenir d'un `alloc` de CET allocator et être vivant.
+    pub unsafe fn usable_size(&self, ptr: *const u8) -> Option<usize> {
+        let ptr = NonNull::new(ptr.cast_mut())?;
+        if Self::is_page_aligned(ptr) {
+            return self.large.pages_of(ptr).map(|pages| pages * PAGE_SIZE);
+        }
+        // SAFETY: précondition => page lisible.
+        let idx = unsafe { self.class_of(ptr) }.ok()?;
+        Some(self.caches[idx].obj_size())
+    }
+
+    /// Vrai si `ptr` pointe dans une page de cet allocateur : objet d'une
+    /// size class ou d'un cache nommé, grosse allocation, pointeur intérieur
+    /// ou zone de header. `ptr` n'est jamais déréférencé : tout pointeur est
+    /// accepté.
+    ///
+    /// Si le provider sait quelles pages il a fournies (`owns_page`), une
+    /// page qu'il n'a pas fournie est écartée immédiatement. Une page fournie
+    /// peut l'avoir été à un autre utilisateur du provider (`provider_mut`,
+    /// provider partagé entre allocateurs) : la réponse positive est donc
+    /// confirmée en parcourant les slabs et les grosses allocations.
+    pub fn owns(&self, ptr: *const u8) -> bool {
+        let Some(nn) = NonNull::new(ptr.cast_mut()) else { return false };
+
+        let off = (ptr as usize) & (PAGE_SIZE - 1);
+        // wrapping_sub conserve la provenance (la page n'est pas lue).
+        let page = NonNull::new(ptr.cast_mut().wrapping_sub(off));
+        if page.and_then(|page| self.provider.owns_page(page)) == Some(false) {
+            return false;
+        }
+
+        self.caches.iter().any(|cache| cache.owns(nn)) || self.named.owns(nn) || self.large.owns(nn)
+    }
+
+    #[inline]
+    fn is_page_aligned(ptr: NonNull<u8>) -> bool {
+        (ptr.as_ptr() as usize).is_multiple_of(PAGE_SIZE)
+    }
+
+    /// Classe de l'objet `ptr`, d'après l'étiquette du header de sa page.
+    ///
+    /// # Safety
+    /// - la page de `ptr` doit être lisible depuis son début (voir `Slab::from_obj`).
+    unsafe fn class_of(&self, ptr: NonNull<u8>) -> Result<usize, AllocError> {
+        // SAFETY: précondition de la fonction.
+        let slab = unsafe { Slab::from_obj(ptr) }.ok_or(AllocError::NotASlab)?;
+        let idx = slab.tag() as usize;
+        match self.caches.get(idx) {
+            Some(cache) if cache.stride() == slab.obj_size() => Ok(idx),
+            _ => Err(AllocError::WrongCache),
+        }
+    }
+
+    /// Redimensionne un bloc à `new_size` bytes (même alignement).
+    ///
+    /// Le pointeur est rendu tel quel si la nouvelle taille reste dans la même
+    /// size class (ou, pour le chemin multi-pages, dans le même nombre de
+    /// pages) ; sinon les données sont déplacées vers la nouvelle destination
+    /// (classe <-> classe, classe <-> pages). Null en cas d'échec : l'ancien
+    /// bloc reste alors valide.
+    ///
+    /// # Safety
+    /// - `ptr` doit provenir d'un `alloc(old_layout)` de CET allocator.
+    /// - en cas de succès, `ptr` ne doit plus être utilisé ; le nouveau bloc se
+    ///   libère avec le layout `new_size`/`old_layout.align()`.
+    pub unsafe fn realloc(&mut self, ptr: *mut u8, old_layout: Layout, new_size: usize) -> *mut u8 {
+        let Ok(new_layout) = Layout::from_size_align(new_size, old_layout.align()) else {
+            return core::ptr::null_mut();
+        };
+        // SAFETY: préconditions de la fonction.
+        unsafe { self.realloc_to(ptr, old_layout, new_layout) }
+    }
+
+    /// Comme `realloc`, avec un layout cible quelconque (alignement compris).
+    ///
+    /// # Safety
+    /// Voir `realloc`.
+    pub(crate) unsafe fn realloc_to(&mut self, ptr: *mut u8, old_layout: Layout, new_layout: Layout) -> *mut u8 {
         if ptr.is_null() {
-            return;
+            return self.alloc(new_layout);
+        }
+
+        let (Ok(old), Ok(new)) = (self.route(old_layout), self.route(new_layout)) else {
+            return core::ptr::null_mut();
+        };
+
+        let in_place = match (old, new) {
+            (Route::Class(a), Route::Class(b)) => a == b,
+            (Route::Large, Route::Large) => {
+                LargeAllocs::pages_for(old_layout.size()) == LargeAllocs::pages_for(new_layout.size())
+            }
+            _ => false,
+        };
+        if in_place {
+            return ptr;
+        }
+
+        let new_ptr = self.alloc(new_layout);
+        if new_ptr.is_null() {
+            return new_ptr;
         }
 
-        let Some(idx) = Self::pick_index(layout) else {
-            debug_assert!(false, "dealloc: unsupported layout");
+        let len = old_layout.size().min(new_layout.size());
+        // SAFETY: deux blocs distincts d'au moins `len` bytes ; `ptr`/`old_layout`
+        // viennent de cet allocateur (précondition).
+        unsafe {
+            core::ptr::copy_nonoverlapping(ptr, new_ptr, len);
+            self.dealloc(ptr, old_layout);
+        }
+        new_ptr
+    }
+
+    /// Crée un cache nommé dédié à des objets `size`/`align`
+    /// (équivalent de `kmem_cache_create`). Il partage le provider de l'allocateur.
+    pub fn create_cache(
+        &mut self,
+        name: &'static str,
+        size: usize,
+        align: usize,
+        flags: CacheFlags,
+    ) -> Result<CacheHandle, CacheError> {
+        self.named.create(name, size, align, flags, None, None)
+    }
+
+    /// Comme `create_cache`, avec un constructeur appliqué à chaque objet
+    /// quand une page est découpée et un destructeur appliqué quand la page
+    /// est rendue (voir `Cache::with_ctor`). Les objets doivent être libérés
+    /// dans leur état construit.
+    pub fn create_cache_with(
+        &mut self,
+        name: &'static str,
+        size: usize,
+        align: usize,
+        flags: CacheFlags,
+        ctor: Option<ObjectFn>,
+        dtor: Option<ObjectFn>,
+    ) -> Result<CacheHandle, CacheError> {
+        self.named.create(name, size, align, flags, ctor, dtor)
+    }
+
+    /// Détruit un cache nommé (`kmem_cache_destroy`) et rend ses pages.
+    ///
+    /// Refusé (`CacheError::Busy`) tant que le cache contient des objets alloués.
+    pub fn destroy_cache(&mut self, handle: CacheHandle) -> Result<(), CacheError> {
+        self.named.destroy(handle, &mut self.provider)
+    }
+
+    /// Recherche un cache nommé vivant.
+    pub fn find_cache(&self, name: &str) -> Option<CacheHandle> {
+        self.named.find(name)
+    }
+
+    /// Nom d'un cache nommé (None si le handle est périmé).
+    pub fn cache_name(&self, handle: CacheHandle) -> Option<&'static str> {
+        self.named.name(handle)
+    }
+
+    /// Cache nommé désigné par `handle` (statistiques, réglages).
+    pub fn named_cache(&self, handle: CacheHandle) -> Option<&Cache> {
+        self.named.get(handle)
+    }
+
+    /// Alloue un objet dans un cache nommé (`kmem_cache_alloc`).
+    ///
+    /// Retourne null si le handle est périmé ou en cas d'OOM.
+    pub fn cache_alloc(&mut self, handle: CacheHandle) -> *mut u8 {
+        match self.named.alloc(handle, &mut self.provider) {
+            Some(p) => p.as_ptr(),
+            None => core::ptr::null_mut(),
+        }
+    }
+
+    /// Libère un objet d'un cache nommé (`kmem_cache_free`).
+    ///
+    /// # Safety
+    /// - `ptr` doit provenir d'un `cache_alloc(handle)` de CET allocator.
+    /// - pas de double-free.
+    pub unsafe fn cache_free(&mut self, handle: CacheHandle, ptr: *mut u8) {
+        let Some(nn) = NonNull::new(ptr) else { return };
+        let Some(cache) = self.named.get_mut(handle) else {
+            debug_assert!(false, "cache_free: stale cache handle");
             return;
         };
+        // SAFETY: ptr vient de ce cache (précondition) ; pages issues de `self.provider`.
+        unsafe { cache.dealloc(nn, &mut self.provider) };
+    }
 
-        let cache = &mut self.caches[idx];
-        let nn = NonNull::new(ptr).expect("ptr checked non-null above");
-	// SAFETY:
-	// - ptr provient d’un alloc(layout) de CET allocator (précondition de dealloc)
-	// - layout route vers ce cache (pick_index identique)
-	// - pas de double free (précondition)
-	unsafe { cache.dealloc(nn) };
+    /// Rend au provider tous les slabs vides de tous les caches.
+    ///
+    /// Retourne le nombre de pages rendues.
+    pub fn shrink(&mut self) -> usize {
+        let provider = &mut self.provider;
+        let released: usize = self.caches.iter_mut().map(|c| c.shrink(provider)).sum();
+        released + self.named.shrink(provider) + self.large.shrink(provider)
+    }
+
+    /// Règle, pour chaque cache, le nombre de slabs vides gardés en réserve
+    /// (voir `Cache::set_empty_limit`).
+    pub fn set_empty_limit(&mut self, limit: usize) {
+        for cache in self.caches.iter_mut() {
+            cache.set_empty_limit(limit);
+        }
+    }
+
+    /// Nombre d'objets (petits et gros) actuellement alloués.
+    pub fn live_objects(&self) -> usize {
+        let small: usize = self.caches.iter().map(Cache::live_objects).sum();
+        small + self.large.live()
     }
 
     pub fn provider_mut(&mut self) -> &mut P {
         &mut self.provider
     }
-}
\ No newline at end of file
+}
+
+/// Démontage : toutes les pages (slabs et grosses allocations) sont rendues
+/// au provider. Les objets encore vivants deviennent invalides.
+///
+/// Avec la feature `leak-report`, les objets encore vivants sont signalés
+/// sur stderr.
+impl<P: PageProvider, const N: usize> Drop for SlabAllocator<P, N> {
+    fn drop(&mut self) {
+        let provider = &mut self.provider;
+
+        for (cache, &size) in self.caches.iter_mut().zip(self.classes.sizes().iter()) {
+            // SAFETY: l'allocateur est détruit => plus aucun objet ne peut être utilisé
+            // via lui ; les pages viennent de `provider`.
+            let live = unsafe { cache.release_all(provider) };
+            report_leak(Leak::Class(size), live);
+        }
+
+        // SAFETY: idem pour les caches nommés.
+        unsafe {
+            self.named
+                .release_all(provider, |name, live| report_leak(Leak::Named(name), live))
+        };
+
+        // SAFETY: idem, les grosses allocations ne sont plus utilisables.
+        let live = unsafe { self.large.release_all(provider) };
+        report_leak(Leak::Large, live);
+    }
+}
+
+/// Origine des objets signalés par `report_leak`.
+#[cfg_attr(not(feature = "leak-report"), allow(dead_code))]
+enum Leak {
+    Class(usize),
+    Named(&'static str),
+    Large,
+}
+
+/// Signale les objets encore vivants au drop.
+#[cfg_attr(not(feature = "leak-report"), allow(unused_variables))]
+fn report_leak(source: Leak, live: usize) {
+    #[cfg(feature = "leak-report")]
+    if live > 0 {
+        match source {
+            Leak::Class(size) => std::eprintln!("slab: cache {size} bytes: {live} objet(s) encore vivant(s) au drop"),
+            Leak::Named(name) => std::eprintln!("slab: cache \"{name}\": {live} objet(s) encore vivant(s) au drop"),
+            Leak::Large => std::eprintln!("slab: {live} grosse(s) allocation(s) encore vivante(s) au drop"),
+        }
+    }
+}
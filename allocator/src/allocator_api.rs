@@ -0,0 +1,75 @@
+//! Trait `Allocator` (feature `allocator-api2`).
+//!
+//! Implémenté pour `&LockedSlabAllocator` : on peut alors écrire
+//! `Vec::new_in(&slab)` ou `Box::new_in(x, &slab)` avec les collections de
+//! `allocator-api2` (ou celles de `alloc` sur nightly, via la feature
+//! `nightly` d'`allocator-api2` qui réexporte `core::alloc::Allocator`).
+
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+use allocator_api2::alloc::{AllocError, Allocator};
+
+use crate::global::LockedSlabAllocator;
+use crate::page_provider::PageProvider;
+
+// SAFETY:
+// - les blocs renvoyés restent valides tant que l'allocateur vit (`&'a` les borne)
+//   et jusqu'à leur `deallocate` ;
+// - copier `&LockedSlabAllocator` donne le même allocateur ;
+// - le spinlock sérialise tous les accès.
+unsafe impl<P: PageProvider, const N: usize> Allocator for &LockedSlabAllocator<P, N> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = NonNull::new(self.lock().alloc(layout)).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let ptr = NonNull::new(self.lock().alloc_zeroed(layout)).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // SAFETY: contrat d'`Allocator::deallocate` (ptr issu de `allocate(layout)`).
+        unsafe { self.lock().dealloc(ptr.as_ptr(), layout) };
+    }
+
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: contrat d'`Allocator::grow`.
+        unsafe { self.resize(ptr, old_layout, new_layout) }
+    }
+
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: contrat d'`Allocator::shrink`.
+        unsafe { self.resize(ptr, old_layout, new_layout) }
+    }
+}
+
+impl<P: PageProvider, const N: usize> LockedSlabAllocator<P, N> {
+    /// `grow`/`shrink` : redimensionne sur place quand la destination ne
+    /// change pas (voir `SlabAllocator::realloc`).
+    ///
+    /// # Safety
+    /// `ptr` vient de cet allocateur avec `old_layout` ; il est libéré en cas de déplacement.
+    unsafe fn resize(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // SAFETY: précondition de la fonction.
+        let new = unsafe { self.lock().realloc_to(ptr.as_ptr(), old_layout, new_layout) };
+        let new = NonNull::new(new).ok_or(AllocError)?;
+        Ok(NonNull::slice_from_raw_parts(new, new_layout.size()))
+    }
+}
@@ -1,85 +1,675 @@
+use core::mem;
 use core::ptr::NonNull;
+use crate::error::AllocError;
+use crate::freelist::FreeNode;
 use crate::page_provider::PageProvider;
-use crate::slab::{Slab, SlabHeader};
+use crate::slab::{FreelistRandom, Slab, SlabConfig, SlabList, NO_TAG};
 
+pub use crate::slab::ObjectFn;
+
+/// Cache d'objets de taille fixe.
+///
+/// Comme dans SLUB, les slabs sont rangés selon leur état :
+/// - `partial` : au moins un objet libre et un objet alloué,
+/// - `full`    : plus aucun objet libre,
+/// - `empty`   : aucun objet alloué.
+///
+/// L'allocation prend toujours la tête de `partial` (ou un slab vide) : O(1).
+///
+/// Au plus `empty_limit` slabs vides sont gardés "au chaud" ; au-delà, les
+/// pages vides sont rendues au provider dès le `dealloc`.
+///
+/// Constructeur / destructeur optionnels (comme `kmem_cache`) : le
+/// constructeur est appliqué à chaque objet quand une nouvelle page est
+/// découpée, le destructeur quand la page est rendue au provider. Un objet
+/// doit donc être libéré dans son état "construit".
 pub struct Cache {
     obj_size: usize,
     align: usize,
-    head: Option<NonNull<SlabHeader>>,
+    /// Pas entre deux objets (`obj_size`, + noeud de freelist si hors objet).
+    stride: usize,
+    /// Position du noeud de freelist dans un objet libre.
+    free_offset: usize,
+    ctor: Option<ObjectFn>,
+    dtor: Option<ObjectFn>,
+    /// Étiquette écrite dans le header de chaque slab (identifie le cache).
+    tag: u16,
+    /// Slabs à freelist sans verrou (voir `with_lock_free_freelist`).
+    lock_free: bool,
+    /// Mélange de la freelist des nouveaux slabs (voir `with_random_freelist`).
+    random: Option<FreelistRandom>,
+    empty_limit: usize,
+    partial: SlabList,
+    full: SlabList,
+    empty: SlabList,
+    /// Slabs gelés par `freeze_slab` (détenus par un cache avant) : seuls
+    /// leurs liens sont touchés ici, pour que `owns` les voie.
+    frozen: SlabList,
 }
 
+/// Nombre de slabs vides conservés par défaut dans chaque cache.
+pub const DEFAULT_EMPTY_LIMIT: usize = 1;
+
 impl Cache {
+    /// Crée un cache d'objets `obj_size`/`align`.
+    ///
+    /// Les objets libres hébergent un noeud de freelist : la taille est donc
+    /// portée à au moins `size_of::<FreeNode>()`, l'alignement à au moins
+    /// `align_of::<FreeNode>()`, et la taille arrondie à l'alignement (pas
+    /// entre objets consécutifs).
     pub const fn new(obj_size: usize, align: usize) -> Self {
+        let min_align = mem::align_of::<FreeNode>();
+        let align = if align < min_align { min_align } else { align };
+
+        let min_size = mem::size_of::<FreeNode>();
+        let mut obj_size = if obj_size < min_size { min_size } else { obj_size };
+        if align.is_power_of_two() {
+            obj_size = (obj_size + align - 1) & !(align - 1);
+        }
+
         Self {
             obj_size,
             align,
-            head: None,
+            stride: obj_size,
+            free_offset: 0,
+            ctor: None,
+            dtor: None,
+            tag: NO_TAG,
+            lock_free: false,
+            random: None,
+            empty_limit: DEFAULT_EMPTY_LIMIT,
+            partial: SlabList::new(),
+            full: SlabList::new(),
+            empty: SlabList::new(),
+            frozen: SlabList::new(),
         }
     }
 
+    /// Ajoute un constructeur et/ou un destructeur d'objets.
+    ///
+    /// Le contenu d'un objet libre doit alors être préservé : le noeud de
+    /// freelist est déplacé juste après l'objet (le pas augmente d'un pointeur).
+    pub const fn with_ctor(mut self, ctor: Option<ObjectFn>, dtor: Option<ObjectFn>) -> Self {
+        self.ctor = ctor;
+        self.dtor = dtor;
+
+        if ctor.is_some() || dtor.is_some() {
+            // obj_size est multiple de `align` (>= align_of::<FreeNode>()).
+            self.free_offset = self.obj_size;
+            let stride = self.obj_size + mem::size_of::<FreeNode>();
+            self.stride = if self.align.is_power_of_two() {
+                (stride + self.align - 1) & !(self.align - 1)
+            } else {
+                stride
+            };
+        } else {
+            self.free_offset = 0;
+            self.stride = self.obj_size;
+        }
+        self
+    }
+
+    /// Étiquette recopiée dans le header de chaque slab : permet au
+    /// propriétaire de retrouver le cache d'un objet à partir de sa page.
+    pub const fn with_tag(mut self, tag: u16) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    /// Les slabs de ce cache utilisent une freelist sans verrou
+    /// (`AtomicFreeList`) : un slab retiré des listes (par exemple gelé pour
+    /// un cache avant) peut alors être servi par plusieurs threads à la fois
+    /// via `Slab::alloc_shared` / `Slab::free_shared`, et les libérations
+    /// distantes d'un slab gelé rejoignent directement sa freelist (voir
+    /// `PerCpuSlabAllocator::with_lock_free_freelist`).
+    ///
+    /// Coût : un CAS par opération, un `u16` par objet dans chaque page, et
+    /// plus de découpage paresseux des pages (`alloc_zeroed` efface donc
+    /// toujours).
+    ///
+    /// Panique si la table des noeuds ne laisse plus de place pour un objet
+    /// (voir `Slab::fits_lock_free`).
+    pub const fn with_lock_free_freelist(mut self) -> Self {
+        assert!(
+            Slab::fits_lock_free(self.stride, self.align),
+            "Cache: objet trop grand pour un slab sans verrou"
+        );
+        self.lock_free = true;
+        self
+    }
+
+    /// Chaque nouveau slab distribue ses objets dans un ordre aléatoire
+    /// (tiré de `random`, un générateur dérivé par slab) au lieu de l'ordre
+    /// des adresses : le placement des objets devient imprévisible.
+    ///
+    /// Comme `with_lock_free_freelist`, supprime le découpage paresseux.
+    pub const fn with_random_freelist(mut self, random: FreelistRandom) -> Self {
+        self.random = Some(random);
+        self
+    }
+
+    /// Vrai si les slabs de ce cache ont une freelist mélangée.
+    #[inline]
+    pub fn is_random(&self) -> bool {
+        self.random.is_some()
+    }
+
+    /// Vrai si les slabs de ce cache ont une freelist sans verrou.
+    #[inline]
+    pub fn is_lock_free(&self) -> bool {
+        self.lock_free
+    }
+
+    /// Étiquette du cache (`NO_TAG` si aucune).
+    #[inline]
+    pub fn tag(&self) -> u16 {
+        self.tag
+    }
+
+    /// Pas entre deux objets dans un slab.
+    #[inline]
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Taille effective des objets (voir `new`).
     #[inline]
     pub fn obj_size(&self) -> usize {
         self.obj_size
     }
 
+    /// Alignement des objets.
+    #[inline]
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Nombre de slabs partiellement remplis.
+    pub fn nr_partial(&self) -> usize {
+        self.partial.len()
+    }
+
+    /// Nombre de slabs pleins.
+    pub fn nr_full(&self) -> usize {
+        self.full.len()
+    }
+
+    /// Nombre de slabs vides (gardés en réserve).
+    pub fn nr_empty(&self) -> usize {
+        self.empty.len()
+    }
+
+    /// Nombre maximal de slabs vides conservés.
+    pub fn empty_limit(&self) -> usize {
+        self.empty_limit
+    }
+
+    /// Règle le nombre de slabs vides conservés. Le surplus éventuel est
+    /// rendu au prochain `dealloc` ou `shrink`.
+    pub fn set_empty_limit(&mut self, limit: usize) {
+        self.empty_limit = limit;
+    }
+
+    /// Rend au provider TOUS les slabs vides. Retourne le nombre de pages rendues.
+    pub fn shrink<P: PageProvider>(&mut self, provider: &mut P) -> usize {
+        self.release_empty(0, provider)
+    }
+
+    /// Vrai si `ptr` pointe dans une page de ce cache (objet, pointeur
+    /// intérieur ou zone du header), slabs gelés compris. Parcourt les slabs :
+    /// O(nombre de slabs), sans jamais lire la mémoire de `ptr`.
+    pub fn owns(&self, ptr: NonNull<u8>) -> bool {
+        [&self.partial, &self.full, &self.empty, &self.frozen]
+            .into_iter()
+            .any(|list| list.iter().any(|slab| slab.contains(ptr)))
+    }
+
+    /// Nombre d'objets actuellement alloués dans ce cache.
+    pub fn live_objects(&self) -> usize {
+        let mut live = 0;
+        for list in [&self.partial, &self.full] {
+            let mut cur = list.head();
+            while let Some(slab) = cur {
+                live += slab.inuse() as usize;
+                // SAFETY: les liens de la liste ne pointent que vers des headers vivants.
+                cur = slab.next_hdr().map(|h| unsafe { Slab::from_hdr(h) });
+            }
+        }
+        live
+    }
+
+    /// Rend TOUTES les pages du cache au provider, y compris celles qui
+    /// contiennent encore des objets alloués (démontage).
+    ///
+    /// Retourne le nombre d'objets encore vivants au moment du démontage.
+    ///
+    /// # Safety
+    /// - `provider` doit être celui qui a fourni les pages de ce cache.
+    /// - aucun objet de ce cache ne doit être utilisé après l'appel.
+    pub unsafe fn release_all<P: PageProvider>(&mut self, provider: &mut P) -> usize {
+        let mut live = 0;
+        for list in [&mut self.partial, &mut self.full, &mut self.empty, &mut self.frozen] {
+            while let Some(slab) = list.pop_front() {
+                live += slab.inuse() as usize;
+                // Destructeur seulement si tous les objets sont libres (donc construits).
+                if slab.is_empty() {
+                    if let Some(dtor) = self.dtor {
+                        slab.for_each_object(dtor);
+                    }
+                }
+                // SAFETY: `slab` n'est plus dans aucune liste ; ses objets ne sont plus utilisés (précondition).
+                let page = unsafe { slab.destroy() };
+                provider.dealloc_page(page);
+            }
+        }
+        live
+    }
+
+    /// Rend les slabs vides au-delà de `keep`.
+    fn release_empty<P: PageProvider>(&mut self, keep: usize, provider: &mut P) -> usize {
+        let mut released = 0;
+        while self.empty.len() > keep {
+            let Some(slab) = self.empty.pop_front() else { break };
+            debug_assert!(slab.is_empty());
+            if let Some(dtor) = self.dtor {
+                slab.for_each_object(dtor);
+            }
+            // SAFETY: `slab` vient de `empty` (vide) et n'est plus dans aucune liste.
+            let page = unsafe { slab.destroy() };
+            provider.dealloc_page(page);
+            released += 1;
+        }
+        released
+    }
+
     pub fn alloc<P: PageProvider>(&mut self, provider: &mut P) -> Option<NonNull<u8>> {
-        // Fast path: chercher un slab avec une place libre
-        let mut cur = self.head;
-        while let Some(hdr) = cur {
-            // SAFETY:
-	    // - `hdr` provient d’un SlabHeader initialisé par Slab::init()
-	    // - header vit au début d’une page slab encore vivante
-	    // - liste intrusive ne contient que des headers valides
-            let mut slab = unsafe { Slab::from_hdr(hdr) };
-            if let Some(p) = slab.alloc() {
-                return Some(p);
+        self.try_alloc(provider).ok()
+    }
+
+    /// Comme `alloc`, avec la raison de l'échec.
+    pub fn try_alloc<P: PageProvider>(&mut self, provider: &mut P) -> Result<NonNull<u8>, AllocError> {
+        self.alloc_obj(provider).map(|(ptr, _)| ptr)
+    }
+
+    /// Alloue un objet rempli de zéros (`obj_size` bytes).
+    ///
+    /// Un objet jamais distribué depuis que sa page a été fournie à zéro
+    /// (`P::ZEROES_PAGES`) n'est pas effacé une seconde fois. Refusé sur un
+    /// cache à constructeur (`AllocError::ZeroedConstructedObject`) : rien
+    /// n'est alloué.
+    pub fn alloc_zeroed<P: PageProvider>(&mut self, provider: &mut P) -> Option<NonNull<u8>> {
+        self.try_alloc_zeroed(provider).ok()
+    }
+
+    /// Comme `alloc_zeroed`, avec la raison de l'échec.
+    pub fn try_alloc_zeroed<P: PageProvider>(&mut self, provider: &mut P) -> Result<NonNull<u8>, AllocError> {
+        if self.ctor.is_some() {
+            return Err(AllocError::ZeroedConstructedObject);
+        }
+        let (ptr, fresh) = self.alloc_obj(provider)?;
+        if !(fresh && P::ZEROES_PAGES) {
+            // SAFETY: objet alloué de `obj_size` bytes, exclusif à l'appelant.
+            unsafe { ptr.as_ptr().write_bytes(0, self.obj_size) };
+        }
+        Ok(ptr)
+    }
+
+    /// Alloue un objet ; le booléen indique s'il est distribué pour la
+    /// première fois depuis le découpage de sa page (voir `Slab::alloc_obj`).
+    fn alloc_obj<P: PageProvider>(&mut self, provider: &mut P) -> Result<(NonNull<u8>, bool), AllocError> {
+        // Fast path: tête de la liste partial, sinon un slab vide en réserve.
+        let mut slab = match self.partial.head() {
+            Some(slab) => slab,
+            None => {
+                let slab = match self.empty.pop_front() {
+                    Some(slab) => slab,
+                    // Slow path: nouveau slab
+                    None => self.grow(provider)?,
+                };
+                // SAFETY: `slab` vient d'être retiré de `empty` (ou créé) : il n'est dans aucune liste.
+                unsafe { self.partial.push_front(slab) };
+                slab
+            }
+        };
+
+        // Invariant des listes : un slab de `partial` (ou vide) a un objet libre.
+        let p = slab.alloc_obj().expect("slab in partial list has no free object");
+
+        if slab.is_full() {
+            // SAFETY: `slab` est dans `partial` (tête) et n'est dans aucune autre liste.
+            unsafe {
+                self.partial.remove(slab);
+                self.full.push_front(slab);
             }
-            cur = slab.next_hdr();
         }
 
-        // Slow path: nouveau slab
-        let page = provider.alloc_page()?;
+        Ok(p)
+    }
 
-        // SAFETY:
-        // - `page` provient du provider => page valide, alignée, writable.
-        // - obj_size/align cohérents pour ce cache.
-        let mut new_slab = unsafe { Slab::init(page, self.obj_size, self.align)? };
+    /// Retire un slab des listes et le gèle pour un cache avant (par CPU /
+    /// par thread) : seul le détenteur utilise ensuite sa freelist, sans
+    /// passer par le cache. Un slab entamé est préféré (comme SLUB).
+    ///
+    /// Les objets d'un slab gelé ne sont plus comptés par `live_objects`.
+    pub fn freeze_slab<P: PageProvider>(&mut self, provider: &mut P) -> Result<Slab, AllocError> {
+        let mut slab = match self.partial.pop_front().or_else(|| self.empty.pop_front()) {
+            Some(slab) => slab,
+            None => self.grow(provider)?,
+        };
+        // SAFETY: `slab` vient d'être retiré des listes (ou créé).
+        unsafe {
+            slab.freeze();
+            self.frozen.push_front(slab);
+        }
+        Ok(slab)
+    }
 
-        // Insérer en tête de liste
+    /// Rend au cache un slab gelé par `freeze_slab` : les objets libérés à
+    /// distance rejoignent sa freelist et le slab retrouve la liste de son état.
+    ///
+    /// # Safety
+    /// - `slab` vient de `freeze_slab` sur CE cache et n'est plus utilisé par
+    ///   son détenteur.
+    /// - `provider` doit être celui qui a fourni les pages de ce cache.
+    pub unsafe fn unfreeze_slab<P: PageProvider>(&mut self, mut slab: Slab, provider: &mut P) {
+        // SAFETY: plus de détenteur => accès exclusif (précondition + `&mut self`) ;
+        // `slab` est dans `frozen` depuis `freeze_slab`.
         unsafe {
-            // # Safety
-            // `new_slab` est valide et son header est dans la page.
-            new_slab.set_next_hdr(self.head);
+            self.frozen.remove(slab);
+            slab.unfreeze();
+            if slab.is_full() {
+                self.full.push_front(slab);
+            } else if slab.is_empty() {
+                self.empty.push_front(slab);
+            } else {
+                self.partial.push_front(slab);
+            }
         }
-        self.head = Some(new_slab.header_ptr());
 
-        new_slab.alloc()
+        if self.empty.len() > self.empty_limit {
+            self.release_empty(self.empty_limit, provider);
+        }
     }
 
+    /// Demande une page au provider et y initialise un slab (hors de toute liste).
+    fn grow<P: PageProvider>(&mut self, provider: &mut P) -> Result<Slab, AllocError> {
+        let page = provider.alloc_page().ok_or(AllocError::OutOfPages)?;
+
+        // SAFETY:
+        // - `page` provient du provider => page valide, alignée, writable.
+        // - obj_size/align cohérents pour ce cache.
+        let cfg = SlabConfig {
+            obj_size: self.stride,
+            align: self.align,
+            free_offset: self.free_offset,
+            ctor: self.ctor,
+            tag: self.tag,
+            lock_free: self.lock_free,
+            random: self.random.as_mut().map(FreelistRandom::fork),
+        };
+        match unsafe { Slab::init_with(page, &cfg) } {
+            Some(slab) => Ok(slab),
+            None => {
+                // Layout impossible dans une page : on ne garde pas la page.
+                provider.dealloc_page(page);
+                Err(AllocError::SlabInitFailed)
+            }
+        }
+    }
+
+    /// Libère un objet en O(1) : le slab propriétaire est retrouvé en
+    /// masquant l'adresse (header au début de la page), sans parcourir la liste.
+    ///
+    /// Si le slab devient vide et que la réserve dépasse `empty_limit`,
+    /// sa page est rendue au provider.
+    ///
     /// # Safety
     /// - `ptr` doit provenir d’un `alloc()` de CE cache (même size-class).
+    /// - `provider` doit être celui qui a fourni les pages de ce cache.
     /// - pas de double-free.
-    pub unsafe fn dealloc(&mut self, ptr: NonNull<u8>) {
-        let mut cur = self.head;
-
-        while let Some(hdr) = cur {
-            // SAFETY:
-	    // - `hdr` provient d’un SlabHeader initialisé par Slab::init()
-	    // - header vit au début d’une page slab encore vivante
-	    // - liste intrusive ne contient que des headers valides
-            let mut slab = unsafe { Slab::from_hdr(hdr) };
-
-            if slab.contains(ptr) {
-                // SAFETY:
-                // - `ptr` appartient bien à ce slab (contains).
-                // - pas de double free (précondition).
-                slab.free(ptr);
-                return;
+    pub unsafe fn dealloc<P: PageProvider>(&mut self, ptr: NonNull<u8>, provider: &mut P) {
+        // SAFETY: préconditions identiques.
+        let res = unsafe { self.try_dealloc(ptr, provider) };
+        debug_assert!(res.is_ok(), "dealloc: {res:?}");
+    }
+
+    /// Comme `dealloc`, mais signale un pointeur qui n'est pas dans un slab
+    /// (`NotASlab`) ou qui appartient à un autre cache (`WrongCache`) ;
+    /// rien n'est libéré dans ces cas.
+    ///
+    /// # Safety
+    /// - la page de `ptr` doit être lisible depuis son début (voir `Slab::from_obj`).
+    /// - `provider` doit être celui qui a fourni les pages de ce cache.
+    /// - pas de double-free.
+    pub unsafe fn try_dealloc<P: PageProvider>(
+        &mut self,
+        ptr: NonNull<u8>,
+        provider: &mut P,
+    ) -> Result<(), AllocError> {
+        // SAFETY: précondition => la page commence par un header lisible.
+        let mut slab = unsafe { Slab::from_obj(ptr) }.ok_or(AllocError::NotASlab)?;
+
+        // Même pas ne suffit pas : deux caches peuvent avoir le même
+        // (classe et cache nommé, par exemple) ; l'étiquette les distingue.
+        if slab.obj_size() != self.stride || slab.tag() != self.tag {
+            return Err(AllocError::WrongCache);
+        }
+
+        // Slab réservé à un cache avant : sa freelist ne nous appartient pas,
+        // l'objet attend dans `remote` (voir `unfreeze_slab`). Un slab non
+        // gelé ne peut pas le devenir tant qu'on tient `&mut self`.
+        // SAFETY: `ptr` appartient au slab et n'est pas déjà libéré.
+        if unsafe { slab.free_remote(ptr) } {
+            return Ok(());
+        }
+
+        let was_full = slab.is_full();
+
+        // SAFETY:
+        // - `ptr` appartient bien à ce slab (même page, header validé).
+        // - pas de double free (précondition).
+        unsafe { slab.free(ptr) };
+
+        // Migration entre listes selon le nouvel état.
+        // SAFETY: l'état avant `free` indique dans quelle liste se trouve `slab`.
+        unsafe {
+            if was_full {
+                self.full.remove(slab);
+                if slab.is_empty() {
+                    self.empty.push_front(slab);
+                } else {
+                    // En queue (comme SLUB) : on continue de remplir les slabs déjà entamés.
+                    self.partial.push_back(slab);
+                }
+            } else if slab.is_empty() {
+                self.partial.remove(slab);
+                self.empty.push_front(slab);
             }
+        }
+
+        if self.empty.len() > self.empty_limit {
+            self.release_empty(self.empty_limit, provider);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(miri)]
+    type Prov = crate::page_provider::TestPageProvider;
+    #[cfg(not(miri))]
+    type Prov = crate::page_provider::StaticPageProvider<16>;
+
+    #[test]
+    fn same_stride_other_cache_is_rejected() {
+        let mut prov = Prov::new();
+        let mut a = Cache::new(64, 8).with_tag(1);
+        let mut b = Cache::new(64, 8).with_tag(2);
+
+        let p = a.try_alloc(&mut prov).expect("alloc");
+        assert_eq!(unsafe { b.try_dealloc(p, &mut prov) }, Err(AllocError::WrongCache));
+        assert_eq!((a.live_objects(), b.nr_partial()), (1, 0));
+
+        unsafe { a.dealloc(p, &mut prov) };
+        a.shrink(&mut prov);
+    }
 
-            cur = slab.next_hdr();
+    #[test]
+    fn slabs_migrate_between_lists() {
+        let mut prov = Prov::new();
+        let mut cache = Cache::new(1024, 1024);
+        cache.set_empty_limit(usize::MAX);
+
+        // 1024/1024 => 3 objets par page (header + 3 objets).
+        let a = cache.alloc(&mut prov).expect("a");
+        assert_eq!((cache.nr_partial(), cache.nr_full(), cache.nr_empty()), (1, 0, 0));
+
+        let b = cache.alloc(&mut prov).expect("b");
+        let c = cache.alloc(&mut prov).expect("c");
+        assert_eq!((cache.nr_partial(), cache.nr_full(), cache.nr_empty()), (0, 1, 0));
+
+        // Nouveau slab : le slab plein n'est plus parcouru.
+        let d = cache.alloc(&mut prov).expect("d");
+        assert_eq!((cache.nr_partial(), cache.nr_full(), cache.nr_empty()), (1, 1, 0));
+
+        unsafe { cache.dealloc(a, &mut prov) };
+        assert_eq!((cache.nr_partial(), cache.nr_full(), cache.nr_empty()), (2, 0, 0));
+
+        unsafe {
+            cache.dealloc(b, &mut prov);
+            cache.dealloc(c, &mut prov);
+        }
+        assert_eq!((cache.nr_partial(), cache.nr_full(), cache.nr_empty()), (1, 0, 1));
+
+        unsafe { cache.dealloc(d, &mut prov) };
+        assert_eq!((cache.nr_partial(), cache.nr_full(), cache.nr_empty()), (0, 0, 2));
+
+        // Un slab vide est réutilisé avant de demander une page.
+        let e = cache.alloc(&mut prov).expect("e");
+        assert_eq!((cache.nr_partial(), cache.nr_full(), cache.nr_empty()), (1, 0, 1));
+        unsafe { cache.dealloc(e, &mut prov) };
+        assert_eq!(cache.shrink(&mut prov), 2);
+        assert_eq!(cache.nr_empty(), 0);
+    }
+
+    #[test]
+    fn empty_limit_returns_pages_on_dealloc() {
+        let mut prov = Prov::new();
+        let mut cache = Cache::new(2048, 2048);
+        cache.set_empty_limit(1);
+
+        // 1 objet de 2048 par page => chaque alloc prend un slab.
+        let ptrs: [_; 3] = core::array::from_fn(|_| cache.alloc(&mut prov).expect("alloc"));
+        assert_eq!(cache.nr_full(), 3);
+
+        for p in ptrs {
+            unsafe { cache.dealloc(p, &mut prov) };
+            assert!(cache.nr_empty() <= 1);
+        }
+        assert_eq!(cache.nr_empty(), 1);
+
+        cache.set_empty_limit(0);
+        assert_eq!(cache.shrink(&mut prov), 1);
+    }
+
+    #[test]
+    fn ctor_runs_once_per_object_and_dtor_on_release() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static CTORS: AtomicUsize = AtomicUsize::new(0);
+        static DTORS: AtomicUsize = AtomicUsize::new(0);
+
+        fn ctor(obj: NonNull<u8>) {
+            CTORS.fetch_add(1, Ordering::Relaxed);
+            unsafe { obj.cast::<u64>().write(0xC0FFEE) };
         }
+        fn dtor(obj: NonNull<u8>) {
+            assert_eq!(unsafe { obj.cast::<u64>().read() }, 0xC0FFEE);
+            DTORS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let mut prov = Prov::new();
+        let mut cache = Cache::new(64, 8).with_ctor(Some(ctor), Some(dtor));
+        cache.set_empty_limit(usize::MAX);
+        // Le noeud de freelist vit après l'objet.
+        assert_eq!(cache.stride(), 72);
+
+        let a = cache.alloc(&mut prov).expect("a");
+        let per_slab = CTORS.load(Ordering::Relaxed);
+        assert!(per_slab > 1);
+        assert_eq!(unsafe { a.cast::<u64>().read() }, 0xC0FFEE);
+
+        // L'état construit survit à free + realloc, sans rappeler le ctor.
+        unsafe { cache.dealloc(a, &mut prov) };
+        let b = cache.alloc(&mut prov).expect("b");
+        assert_eq!(b, a);
+        assert_eq!(unsafe { b.cast::<u64>().read() }, 0xC0FFEE);
+        assert_eq!(CTORS.load(Ordering::Relaxed), per_slab);
+
+        // Effacer l'objet détruirait l'état construit : refusé, sans allocation.
+        assert_eq!(cache.try_alloc_zeroed(&mut prov), Err(AllocError::ZeroedConstructedObject));
+        assert_eq!(cache.live_objects(), 1);
+
+        unsafe { cache.dealloc(b, &mut prov) };
+        assert_eq!(DTORS.load(Ordering::Relaxed), 0);
+        assert_eq!(cache.shrink(&mut prov), 1);
+        assert_eq!(DTORS.load(Ordering::Relaxed), per_slab);
+    }
+
+    #[test]
+    #[should_panic(expected = "slab sans verrou")]
+    fn lock_free_rejects_objects_without_room_for_the_node_table() {
+        // Tient dans une page ordinaire, plus une fois la table des noeuds ajoutée.
+        let size = (0..crate::PAGE_SIZE).rev().find(|&s| Slab::fits(s, 8)).unwrap();
+        assert!(!Slab::fits_lock_free(size, 8));
+        let _ = Cache::new(size, 8).with_lock_free_freelist();
+    }
+
+    #[test]
+    fn owns_covers_frozen_slabs() {
+        let mut prov = Prov::new();
+        let mut cache = Cache::new(64, 8);
+
+        let mut slab = cache.freeze_slab(&mut prov).expect("freeze");
+        let a = slab.alloc().expect("a");
+        assert!(cache.owns(a));
+
+        // SAFETY: slab gelé par ce cache, plus utilisé.
+        unsafe { cache.unfreeze_slab(slab, &mut prov) };
+        assert!(cache.owns(a));
+        unsafe { cache.dealloc(a, &mut prov) };
+
+        // Un slab resté gelé au démontage est rendu aussi.
+        let slab = cache.freeze_slab(&mut prov).expect("freeze");
+        assert!(cache.owns(NonNull::new(slab.page_base()).unwrap()));
+        assert_eq!(unsafe { cache.release_all(&mut prov) }, 0);
+        assert!(!cache.owns(NonNull::new(slab.page_base()).unwrap()));
+    }
+
+    #[test]
+    fn owns_covers_interior_and_header_pointers() {
+        let mut prov = Prov::new();
+        let mut cache = Cache::new(64, 8);
+
+        let a = cache.alloc(&mut prov).expect("a");
+        assert!(cache.owns(a));
+        assert!(cache.owns(unsafe { a.add(63) }));
+        // Début de page : header du slab.
+        let base = a.as_ptr().wrapping_sub(a.as_ptr() as usize % crate::PAGE_SIZE);
+        assert!(cache.owns(NonNull::new(base).unwrap()));
+
+        let other = Cache::new(64, 8);
+        assert!(!other.owns(a));
+
+        let local = 0u64;
+        assert!(!cache.owns(NonNull::from(&local).cast()));
 
-        debug_assert!(false, "dealloc: ptr not found in cache slabs");
+        unsafe { cache.dealloc(a, &mut prov) };
+        cache.set_empty_limit(0);
+        cache.shrink(&mut prov);
+        assert!(!cache.owns(a));
     }
 }
@@ -0,0 +1,283 @@
+//! Allocateur partagé entre threads avec un verrou par cache.
+//!
+//! `LockedSlabAllocator` sérialise toutes les size classes derrière un seul
+//! spinlock. Ici chaque `Cache` a son propre verrou, le chemin multi-pages le
+//! sien, et le provider un dernier : deux allocations de classes différentes
+//! avancent en parallèle et ne se croisent que lorsqu'elles demandent une page.
+//!
+//! Ordre de verrouillage : cache (ou chemin multi-pages) puis provider, jamais
+//! l'inverse. Le provider n'est verrouillé que le temps d'un appel
+//! (`alloc_page`, `dealloc_page`, ...), via `SharedProvider`.
+//!
+//! Verrous : `SpinLock` par défaut, `std::sync::Mutex` avec la feature
+//! `std-mutex` (voir `sync::Lock`).
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::ops::DerefMut;
+use core::ptr::NonNull;
+
+use crate::allocator::{route, Route};
+use crate::cache::Cache;
+use crate::error::AllocError;
+use crate::large::LargeAllocs;
+use crate::page_provider::{PageProvider, PAGE_SIZE};
+use crate::size_class::{SizeClasses, DEFAULT_SIZE_CLASSES, FINE_SIZE_CLASSES};
+use crate::sync::Lock;
+
+/// Allocateur slab `Sync` : un verrou par size class.
+///
+/// ```ignore
+/// static SLAB: ConcurrentSlabAllocator<StaticPageProvider<1024>> =
+///     ConcurrentSlabAllocator::new(StaticPageProvider::new());
+/// ```
+pub struct ConcurrentSlabAllocator<P: PageProvider, const N: usize = 9> {
+    classes: SizeClasses<N>,
+    caches: [Lock<Cache>; N],
+    large: Lock<LargeAllocs>,
+    provider: Lock<P>,
+}
+
+// SAFETY: les slabs et grosses allocations sont des pages possédées
+// exclusivement par cet allocateur (obtenues via `provider`) ; chaque structure
+// qui les référence est protégée par son propre verrou.
+unsafe impl<P: PageProvider + Send, const N: usize> Send for ConcurrentSlabAllocator<P, N> {}
+// SAFETY: idem, tout accès partagé passe par un verrou.
+unsafe impl<P: PageProvider + Send, const N: usize> Sync for ConcurrentSlabAllocator<P, N> {}
+
+impl<P: PageProvider> ConcurrentSlabAllocator<P> {
+    /// Allocateur avec la table par défaut (8..2048).
+    pub const fn new(provider: P) -> Self {
+        Self::with_classes(provider, DEFAULT_SIZE_CLASSES)
+    }
+}
+
+impl<P: PageProvider> ConcurrentSlabAllocator<P, 26> {
+    /// Allocateur avec la table fine (voir `SlabAllocator::new_fine`).
+    pub const fn new_fine(provider: P) -> Self {
+        Self::with_classes(provider, FINE_SIZE_CLASSES)
+    }
+}
+
+impl<P: PageProvider, const N: usize> ConcurrentSlabAllocator<P, N> {
+    /// Allocateur avec une table de size classes personnalisée.
+    pub const fn with_classes(provider: P, classes: SizeClasses<N>) -> Self {
+        let mut caches = [const { Lock::new(Cache::new(0, 1)) }; N];
+        let mut i = 0;
+        while i < N {
+            caches[i] = Lock::new(Cache::new(classes.size(i), classes.align(i)).with_tag(i as u16));
+            i += 1;
+        }
+
+        Self {
+            classes,
+            caches,
+            large: Lock::new(LargeAllocs::new()),
+            provider: Lock::new(provider),
+        }
+    }
+
+    /// Caches de classe à freelist sans verrou (voir
+    /// `Cache::with_lock_free_freelist`) ; utile derrière
+    /// `PerCpuSlabAllocator`, qui gèle leurs slabs.
+    pub const fn with_lock_free_freelist(mut self) -> Self {
+        self.set_lock_free_freelist();
+        self
+    }
+
+    pub(crate) const fn set_lock_free_freelist(&mut self) {
+        let mut i = 0;
+        while i < N {
+            let cache = Cache::new(self.classes.size(i), self.classes.align(i)).with_tag(i as u16);
+            self.caches[i] = Lock::new(cache.with_lock_free_freelist());
+            i += 1;
+        }
+    }
+
+    /// Table de size classes utilisée.
+    pub fn classes(&self) -> &SizeClasses<N> {
+        &self.classes
+    }
+
+    pub(crate) fn shared_provider(&self) -> SharedProvider<'_, P> {
+        SharedProvider::new(&self.provider)
+    }
+
+    /// Cache de la classe `idx` (verrou compris), pour les couches avant.
+    pub(crate) fn cache(&self, idx: usize) -> &Lock<Cache> {
+        &self.caches[idx]
+    }
+
+    /// Alloue un bloc pour `layout` ; null en cas d'échec.
+    pub fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.try_alloc(layout).map_or(core::ptr::null_mut(), NonNull::as_ptr)
+    }
+
+    /// Comme `alloc`, avec la raison de l'échec.
+    pub fn try_alloc(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        let mut provider = self.shared_provider();
+        match route(&self.classes, layout)? {
+            Route::Class(idx) => self.caches[idx].lock().try_alloc(&mut provider),
+            Route::Large => self.large.lock().alloc(layout.size(), &mut provider),
+        }
+    }
+
+    /// Comme `alloc`, mais le bloc est rempli de zéros (voir `SlabAllocator::alloc_zeroed`).
+    pub fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let mut provider = self.shared_provider();
+        let res = match route(&self.classes, layout) {
+            Ok(Route::Class(idx)) => self.caches[idx].lock().try_alloc_zeroed(&mut provider),
+            Ok(Route::Large) => self.large.lock().alloc_zeroed(layout.size(), &mut provider),
+            Err(e) => Err(e),
+        };
+        res.map_or(core::ptr::null_mut(), NonNull::as_ptr)
+    }
+
+    /// # Safety
+    /// - `ptr` doit provenir d’un `alloc(layout)` de CET allocator.
+    /// - `layout` doit être identique à celui utilisé lors de l'allocation.
+    /// - pas de double-free.
+    pub unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let Some(nn) = NonNull::new(ptr) else { return };
+        // SAFETY: préconditions identiques.
+        let res = unsafe { self.try_dealloc(nn, layout) };
+        debug_assert!(res.is_ok(), "dealloc: {res:?}");
+    }
+
+    /// Comme `dealloc`, avec la raison de l'échec (voir `SlabAllocator::try_dealloc`).
+    ///
+    /// # Safety
+    /// - `ptr` doit provenir d’un `alloc` de CET allocator, non encore libéré.
+    pub unsafe fn try_dealloc(&self, ptr: NonNull<u8>, layout: Layout) -> Result<(), AllocError> {
+        let mut provider = self.shared_provider();
+        match route(&self.classes, layout)? {
+            // SAFETY: ptr vient de ce cache (même route), pages issues de `provider`.
+            Route::Class(idx) => unsafe { self.caches[idx].lock().try_dealloc(ptr, &mut provider) },
+            Route::Large => {
+                // SAFETY: si `ptr` est une grosse allocation, elle n'est pas déjà libérée.
+                let found = unsafe { self.large.lock().dealloc(ptr, &mut provider) };
+                found.then_some(()).ok_or(AllocError::UnknownLargeAllocation)
+            }
+        }
+    }
+
+    /// Rend au provider tous les slabs vides ; retourne le nombre de pages rendues.
+    pub fn shrink(&self) -> usize {
+        let mut provider = self.shared_provider();
+        let released: usize = self.caches.iter().map(|c| c.lock().shrink(&mut provider)).sum();
+        released + self.large.lock().shrink(&mut provider)
+    }
+
+    /// Règle, pour chaque cache, le nombre de slabs vides gardés en réserve.
+    pub fn set_empty_limit(&self, limit: usize) {
+        for cache in self.caches.iter() {
+            cache.lock().set_empty_limit(limit);
+        }
+    }
+
+    /// Nombre d'objets (petits et gros) actuellement alloués.
+    ///
+    /// Photographie non atomique : les caches sont lus l'un après l'autre.
+    pub fn live_objects(&self) -> usize {
+        let small: usize = self.caches.iter().map(|c| c.lock().live_objects()).sum();
+        small + self.large.lock().live()
+    }
+
+    /// Vrai si `ptr` pointe dans une page de cet allocateur (voir
+    /// `SlabAllocator::owns`), slabs gelés par `PerCpuSlabAllocator` compris.
+    /// `ptr` n'est jamais déréférencé.
+    pub fn owns(&self, ptr: *const u8) -> bool {
+        let Some(nn) = NonNull::new(ptr.cast_mut()) else { return false };
+
+        let off = (ptr as usize) & (PAGE_SIZE - 1);
+        // wrapping_sub conserve la provenance (la page n'est pas lue).
+        let page = NonNull::new(ptr.cast_mut().wrapping_sub(off));
+        if page.and_then(|page| self.provider.lock().owns_page(page)) == Some(false) {
+            return false;
+        }
+
+        self.caches.iter().any(|cache| cache.lock().owns(nn)) || self.large.lock().owns(nn)
+    }
+
+    /// Verrouille le provider (statistiques, inspection). Ne pas allouer
+    /// depuis cet allocateur tant que la garde est vivante.
+    pub fn lock_provider(&self) -> impl DerefMut<Target = P> + '_ {
+        self.provider.lock()
+    }
+
+    /// Accès direct au provider (exclusivité => aucun verrou nécessaire).
+    pub fn provider_mut(&mut self) -> &mut P {
+        self.provider.get_mut()
+    }
+}
+
+/// Démontage : toutes les pages sont rendues au provider.
+impl<P: PageProvider, const N: usize> Drop for ConcurrentSlabAllocator<P, N> {
+    fn drop(&mut self) {
+        let provider = self.provider.get_mut();
+        for cache in self.caches.iter_mut() {
+            // SAFETY: l'allocateur est détruit => plus aucun objet utilisable ;
+            // les pages viennent de `provider`.
+            unsafe { cache.get_mut().release_all(provider) };
+        }
+        // SAFETY: idem pour les grosses allocations.
+        unsafe { self.large.get_mut().release_all(provider) };
+    }
+}
+
+// SAFETY:
+// - `alloc` renvoie soit null, soit un bloc respectant `layout`.
+// - `dealloc` reçoit un ptr/layout issus de `alloc` (contrat de GlobalAlloc).
+// - chaque structure partagée est protégée par son verrou.
+unsafe impl<P: PageProvider + Send, const N: usize> GlobalAlloc for ConcurrentSlabAllocator<P, N> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ConcurrentSlabAllocator::alloc(self, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ConcurrentSlabAllocator::alloc_zeroed(self, layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: précondition de GlobalAlloc::dealloc (ptr issu de alloc(layout)).
+        unsafe { ConcurrentSlabAllocator::dealloc(self, ptr, layout) };
+    }
+}
+
+/// Provider partagé : verrouille le provider réel le temps de chaque appel.
+///
+/// Passé aux `Cache` à la place de `&mut P`, il permet de ne prendre le verrou
+/// du provider que lorsqu'un cache demande ou rend une page.
+pub(crate) struct SharedProvider<'a, P> {
+    lock: &'a Lock<P>,
+}
+
+impl<'a, P> SharedProvider<'a, P> {
+    /// Provider partagé derrière `lock`.
+    pub(crate) fn new(lock: &'a Lock<P>) -> Self {
+        Self { lock }
+    }
+}
+
+impl<P: PageProvider> PageProvider for SharedProvider<'_, P> {
+    const ZEROES_PAGES: bool = P::ZEROES_PAGES;
+
+    fn alloc_page(&mut self) -> Option<NonNull<u8>> {
+        self.lock.lock().alloc_page()
+    }
+
+    fn dealloc_page(&mut self, ptr: NonNull<u8>) {
+        self.lock.lock().dealloc_page(ptr)
+    }
+
+    fn alloc_pages(&mut self, count: usize) -> Option<NonNull<u8>> {
+        self.lock.lock().alloc_pages(count)
+    }
+
+    fn dealloc_pages(&mut self, ptr: NonNull<u8>, count: usize) {
+        self.lock.lock().dealloc_pages(ptr, count)
+    }
+
+    fn owns_page(&self, page: NonNull<u8>) -> Option<bool> {
+        self.lock.lock().owns_page(page)
+    }
+}
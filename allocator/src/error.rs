@@ -0,0 +1,29 @@
+//! Erreurs de l'API faillible (`SlabAllocator::try_alloc`, `try_dealloc`).
+//!
+//! L'API "pointeur brut" (`alloc` renvoie null) reste disponible : elle
+//! s'appuie sur celle-ci et ne fait qu'écraser la raison de l'échec.
+
+/// Raison d'un échec d'allocation ou de libération.
+///
+/// Toute taille est servie (size class ou pages contiguës) : seul
+/// l'alignement peut rendre un `Layout` impossible.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AllocError {
+    /// Alignement supérieur à `PAGE_SIZE` : aucune destination possible.
+    AlignmentTooLarge { align: usize },
+    /// Le provider n'a plus de page (ou pas de suite contiguë assez longue).
+    OutOfPages,
+    /// `Slab::init` a refusé la page (objet ou alignement impossibles dans une page).
+    SlabInitFailed,
+    /// Libération : le pointeur n'est pas dans un slab vivant.
+    NotASlab,
+    /// Libération : le slab appartient à un autre cache (layout différent de
+    /// celui de l'allocation, ou autre cache de même taille : étiquette différente).
+    WrongCache,
+    /// Libération : aucune grosse allocation ne commence à ce pointeur.
+    UnknownLargeAllocation,
+    /// `alloc_zeroed` sur un cache à constructeur : effacer l'objet
+    /// détruirait l'état construit (le noyau traite `__GFP_ZERO` sur un
+    /// cache à ctor comme un bug de l'appelant).
+    ZeroedConstructedObject,
+}
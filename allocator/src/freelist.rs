@@ -1,16 +1,31 @@
 //! Freelist intrusive minimale.
 //!
 //! Les objets libres stockent un pointeur vers le prochain objet libre
-//! dans leurs premiers octets.
+//! dans leurs premiers octets, ou à `offset` octets du début de l'objet
+//! quand le contenu de l'objet doit survivre à la libération (constructeur).
+//!
+//! Avec la feature `freelist-hardened` (équivalent de
+//! `CONFIG_SLAB_FREELIST_HARDENED`), les listes ne stockent plus le pointeur
+//! brut mais `next ^ secret du slab ^ adresse du noeud`, et chaque suivant
+//! décodé doit désigner un objet de la page du slab (voir `NodeCodec`) : une
+//! écriture après libération ne suffit plus à rediriger l'allocation
+//! suivante. Une corruption détectée arrête le programme (`corrupted`).
 
+#[cfg(feature = "freelist-hardened")]
+use core::fmt;
 use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+
+use crate::page_provider::PAGE_SIZE;
+use crate::sync::{AtomicU16, AtomicUsize};
 
 /// Noeud stocké dans un objet libre.
 ///
-/// Le champ `next` est écrit directement dans la mémoire de l'objet.
+/// Le champ `next` (prochain objet libre) est écrit directement dans la
+/// mémoire de l'objet.
 #[repr(C)]
 pub struct FreeNode {
-    next: Option<NonNull<FreeNode>>,
+    next: Option<NonNull<u8>>,
 }
 
 impl FreeNode {
@@ -22,7 +37,8 @@ impl FreeNode {
     ///   pouvant contenir un `FreeNode`.
     /// - La mémoire pointée doit être considérée comme libre
     ///   (aucune donnée valide ne doit y être conservée).
-    unsafe fn write(ptr: NonNull<u8>, next: Option<NonNull<FreeNode>>) {
+    #[cfg(not(feature = "freelist-hardened"))]
+    pub(crate) unsafe fn write(ptr: NonNull<u8>, next: Option<NonNull<u8>>) {
         let node = ptr.as_ptr() as *mut FreeNode;
         (*node).next = next;
     }
@@ -33,21 +49,291 @@ impl FreeNode {
     ///
     /// - `ptr` doit pointer vers un objet précédemment initialisé
     ///   comme `FreeNode`.
-    unsafe fn read(ptr: NonNull<u8>) -> Option<NonNull<FreeNode>> {
+    #[cfg(not(feature = "freelist-hardened"))]
+    pub(crate) unsafe fn read(ptr: NonNull<u8>) -> Option<NonNull<u8>> {
         let node = ptr.as_ptr() as *const FreeNode;
         (*node).next
     }
+
+    /// Écrit un noeud encodé (mot quelconque, pas forcément une adresse).
+    ///
+    /// # Safety
+    ///
+    /// Comme `write`.
+    #[cfg(feature = "freelist-hardened")]
+    unsafe fn write_encoded(ptr: NonNull<u8>, value: usize) {
+        ptr.as_ptr().cast::<usize>().write(value);
+    }
+
+    /// Lit un noeud écrit par `write_encoded`.
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` doit pointer vers un noeud écrit par `write_encoded`.
+    #[cfg(feature = "freelist-hardened")]
+    unsafe fn read_encoded(ptr: NonNull<u8>) -> usize {
+        ptr.as_ptr().cast::<usize>().read()
+    }
+}
+
+/// Graine des secrets de slab ; avance à chaque secret tiré.
+#[cfg(feature = "freelist-hardened")]
+static SECRET_SEED: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(0x2545_F491_4F6C_DD1D);
+
+/// Fixe la graine des secrets de freelist (à appeler au démarrage avec une
+/// valeur imprévisible, p. ex. issue du RNG matériel). Sans la feature
+/// `freelist-hardened`, sans effet.
+pub fn set_hardening_seed(seed: u64) {
+    #[cfg(feature = "freelist-hardened")]
+    SECRET_SEED.store(seed, Ordering::Relaxed);
+    #[cfg(not(feature = "freelist-hardened"))]
+    let _ = seed;
+}
+
+/// Tire le secret d'un nouveau slab situé à `page` (0 sans la feature
+/// `freelist-hardened`).
+pub(crate) fn slab_secret(page: usize) -> usize {
+    #[cfg(feature = "freelist-hardened")]
+    {
+        // splitmix64 : chaque slab a son secret, même à la même adresse.
+        let mut z = SECRET_SEED.fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed) ^ page as u64;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        (z ^ (z >> 31)) as usize
+    }
+    #[cfg(not(feature = "freelist-hardened"))]
+    {
+        let _ = page;
+        0
+    }
+}
+
+/// Arrête le programme sur une corruption de freelist.
+///
+/// Sans déroulement : l'allocateur peut être appelé depuis
+/// `GlobalAlloc::alloc`, qu'une panique ne doit pas traverser, et l'état
+/// corrompu ne doit plus servir.
+#[cfg(feature = "freelist-hardened")]
+#[cold]
+#[inline(never)]
+pub(crate) fn corrupted(args: fmt::Arguments<'_>) -> ! {
+    #[cfg(feature = "std")]
+    {
+        std::eprintln!("allocator: {args}");
+        std::process::abort()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        // Une panique ne peut pas sortir d'une fonction `extern "C"` : le
+        // gestionnaire affiche le message, puis le programme s'arrête.
+        extern "C" fn no_unwind(args: &fmt::Arguments<'_>) -> ! {
+            panic!("{args}")
+        }
+        no_unwind(&args)
+    }
+}
+
+/// Encodage et validation des noeuds des listes d'un slab.
+///
+/// Connaît la disposition des objets dans la page (`start`, `stride`,
+/// `capacity`). Avec `freelist-hardened`, un noeud stocke `suivant ^ secret ^
+/// adresse du noeud`, et un suivant décodé qui n'est pas l'un des `capacity`
+/// objets (avant `start`, entre deux objets, dans l'emplacement partiel de
+/// fin de page ou hors de la page) arrête le programme. Sans la feature, les
+/// noeuds sont en clair.
+#[derive(Copy, Clone)]
+pub struct NodeCodec {
+    /// Offset (dans la page) du premier objet.
+    start: usize,
+    /// Pas entre deux objets.
+    stride: usize,
+    /// Nombre d'objets du slab.
+    #[cfg(feature = "freelist-hardened")]
+    capacity: usize,
+    /// Secret du slab (voir `slab_secret`).
+    #[cfg(feature = "freelist-hardened")]
+    secret: usize,
+}
+
+impl Default for NodeCodec {
+    fn default() -> Self {
+        Self::whole_page()
+    }
+}
+
+impl NodeCodec {
+    /// `capacity` objets à partir de l'offset `start` de la page, espacés de
+    /// `stride` (`start + capacity * stride <= PAGE_SIZE`) ; noeuds encodés
+    /// avec `secret`.
+    pub const fn new(start: usize, stride: usize, capacity: usize, secret: usize) -> Self {
+        #[cfg(not(feature = "freelist-hardened"))]
+        let _ = secret;
+        debug_assert!(start + capacity * stride <= PAGE_SIZE);
+        Self {
+            start,
+            stride,
+            #[cfg(feature = "freelist-hardened")]
+            capacity,
+            #[cfg(feature = "freelist-hardened")]
+            secret,
+        }
+    }
+
+    /// Codec sans secret dont les objets sont tous les `FreeNode` de la page.
+    const fn whole_page() -> Self {
+        let size = core::mem::size_of::<FreeNode>();
+        Self::new(0, size, PAGE_SIZE / size, 0)
+    }
+
+    /// Index de l'objet `ptr` de la page `base`.
+    fn index(&self, base: NonNull<u8>, ptr: NonNull<u8>) -> usize {
+        (ptr.as_ptr() as usize - base.as_ptr() as usize - self.start) / self.stride
+    }
+
+    /// Objet d'index `idx` de la page `base`.
+    ///
+    /// # Safety
+    ///
+    /// - l'objet `idx` est dans la page.
+    unsafe fn object(&self, base: NonNull<u8>, idx: usize) -> NonNull<u8> {
+        base.add(self.start + idx * self.stride)
+    }
+
+    /// Écrit dans `slot` le noeud désignant `next`.
+    ///
+    /// # Safety
+    ///
+    /// - `slot` est valide et aligné pour un `FreeNode`, dans un objet libre.
+    #[cfg(not(feature = "freelist-hardened"))]
+    unsafe fn store(&self, slot: NonNull<u8>, next: Option<NonNull<u8>>) {
+        FreeNode::write(slot, next);
+    }
+
+    /// Lit le noeud `slot` de l'objet `obj`.
+    ///
+    /// # Safety
+    ///
+    /// - `slot` a été écrit par `store` avec ce même codec.
+    #[cfg(not(feature = "freelist-hardened"))]
+    unsafe fn load(&self, _obj: NonNull<u8>, slot: NonNull<u8>) -> Option<NonNull<u8>> {
+        FreeNode::read(slot)
+    }
+
+    /// Écrit dans `slot` le noeud encodé : `next ^ secret ^ adresse du noeud`.
+    ///
+    /// # Safety
+    ///
+    /// - `slot` est valide et aligné pour un `FreeNode`, dans un objet libre.
+    #[cfg(feature = "freelist-hardened")]
+    unsafe fn store(&self, slot: NonNull<u8>, next: Option<NonNull<u8>>) {
+        let next = next.map_or(0, |p| p.as_ptr() as usize);
+        FreeNode::write_encoded(slot, next ^ self.secret ^ slot.as_ptr() as usize);
+    }
+
+    /// Décode le noeud `slot` de l'objet `obj` et vérifie que le suivant est
+    /// un objet de la même page.
+    ///
+    /// # Safety
+    ///
+    /// - `slot` est lisible (le contenu peut avoir été corrompu).
+    #[cfg(feature = "freelist-hardened")]
+    unsafe fn load(&self, obj: NonNull<u8>, slot: NonNull<u8>) -> Option<NonNull<u8>> {
+        let next = FreeNode::read_encoded(slot) ^ self.secret ^ slot.as_ptr() as usize;
+        if next == 0 {
+            return None;
+        }
+        let page = obj.as_ptr() as usize & !(PAGE_SIZE - 1);
+        let off = next.wrapping_sub(page);
+        if off < self.start
+            || !(off - self.start).is_multiple_of(self.stride)
+            || (off - self.start) / self.stride >= self.capacity
+        {
+            corrupted(format_args!(
+                "freelist corrompue : le noeud {slot:p} désigne {next:#x}, pas un objet de la page {page:#x}"
+            ));
+        }
+        // Même page que `obj` : on reprend sa provenance.
+        NonNull::new(obj.as_ptr().with_addr(next))
+    }
+
+    /// Noeud de la table de `AtomicFreeList` pour l'objet `idx` : `next`
+    /// (index du suivant + 1, 0 = fin), encodé avec le secret et l'index.
+    fn encode_index(&self, idx: usize, next: usize) -> u16 {
+        #[cfg(feature = "freelist-hardened")]
+        let next = next ^ self.secret ^ idx;
+        #[cfg(not(feature = "freelist-hardened"))]
+        let _ = idx;
+        next as u16
+    }
+
+    /// Inverse de `encode_index` ; avec `freelist-hardened`, vérifie que le
+    /// suivant est l'un des `capacity` objets du slab.
+    fn decode_index(&self, idx: usize, node: u16) -> usize {
+        #[cfg(feature = "freelist-hardened")]
+        {
+            let next = (node as usize ^ self.secret ^ idx) & u16::MAX as usize;
+            if next > self.capacity {
+                corrupted(format_args!("freelist corrompue : le noeud de l'objet {idx} désigne l'index {next}"));
+            }
+            next
+        }
+        #[cfg(not(feature = "freelist-hardened"))]
+        {
+            let _ = idx;
+            node as usize
+        }
+    }
+
+    /// Arrête le programme si `ptr` est déjà en tête de liste (double
+    /// libération immédiate) ; sans `freelist-hardened`, sans effet.
+    fn check_double_free(&self, head: Option<NonNull<u8>>, ptr: NonNull<u8>) {
+        #[cfg(feature = "freelist-hardened")]
+        if head == Some(ptr) {
+            corrupted(format_args!("double libération de {ptr:p}"));
+        }
+        #[cfg(not(feature = "freelist-hardened"))]
+        let _ = (head, ptr);
+    }
 }
 
 /// Freelist intrusive LIFO.
+///
+/// `head` pointe sur le premier objet libre ; son `FreeNode` est à `offset`.
+/// Tous les objets d'une même liste sont dans une même page.
 pub struct FreeList {
-    head: Option<NonNull<FreeNode>>,
+    head: Option<NonNull<u8>>,
+    offset: usize,
+    /// Encodage des noeuds (voir `NodeCodec`).
+    codec: NodeCodec,
+}
+
+impl Default for FreeList {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FreeList {
-    /// Crée une freelist vide.
+    /// Crée une freelist vide (noeud au début de chaque objet).
     pub const fn new() -> Self {
-        Self { head: None }
+        Self::with_offset(0)
+    }
+
+    /// Crée une freelist vide dont les noeuds sont stockés à `offset`
+    /// octets du début de chaque objet.
+    pub const fn with_offset(offset: usize) -> Self {
+        Self {
+            head: None,
+            offset,
+            codec: NodeCodec::whole_page(),
+        }
+    }
+
+    /// Fixe l'encodage des noeuds (disposition des objets et secret du
+    /// slab) ; à appeler sur une liste vide.
+    pub const fn with_codec(mut self, codec: NodeCodec) -> Self {
+        self.codec = codec;
+        self
     }
 
     /// Retourne vrai si la freelist est vide.
@@ -57,27 +343,671 @@ impl FreeList {
 
     /// Ajoute un objet à la freelist.
     ///
+    /// Avec `freelist-hardened`, arrête le programme si l'objet est déjà en
+    /// tête (double libération).
+    ///
     /// # Safety
     ///
-    /// - `ptr` doit être aligné correctement pour `FreeNode`.
+    /// - `ptr + offset` doit être aligné correctement pour `FreeNode`.
     /// - `ptr` doit pointer vers une zone mémoire libre
     ///   (pas de double free).
     /// - L'objet doit appartenir au slab correspondant.
     pub unsafe fn push(&mut self, ptr: NonNull<u8>) {
         let next = self.head;
-        FreeNode::write(ptr, next);
-        self.head = Some(ptr.cast());
+        self.codec.check_double_free(next, ptr);
+        self.codec.store(ptr.add(self.offset), next);
+        self.head = Some(ptr);
     }
 
     /// Retire et retourne un objet libre.
     ///
+    /// Avec `freelist-hardened`, arrête le programme si le noeud de la tête
+    /// a été corrompu (suivant décodé qui n'est pas un objet de la page).
+    ///
     /// # Safety
     ///
     /// - Tous les pointeurs stockés dans la freelist doivent être valides.
     pub unsafe fn pop(&mut self) -> Option<NonNull<u8>> {
         let head = self.head?;
-        let next = FreeNode::read(head.cast());
+        let next = self.codec.load(head, head.add(self.offset));
         self.head = next;
-        Some(head.cast())
+        Some(head)
+    }
+}
+
+/// Bit de poids faible de `RemoteList::head` : slab gelé (les objets, alignés
+/// au moins comme un `FreeNode`, ont ce bit à 0).
+const FROZEN: usize = 1;
+
+/// Pile des objets libérés à distance dans un slab gelé, sans verrou.
+///
+/// N'importe quel thread y empile (`push`, CAS sur `head`) ; seul le
+/// propriétaire du slab la vide, en la prenant d'un bloc (`drain_into`).
+/// Comme personne ne dépile un élément isolé, pas de problème d'ABA.
+///
+/// L'état "gelé" vit dans `head` : un `push` sur un slab dégelé échoue, et
+/// dégeler vide la pile dans le même échange atomique. Aucun objet ne peut
+/// donc rester coincé dans la pile d'un slab redevenu ordinaire.
+pub struct RemoteList {
+    head: AtomicUsize,
+    /// Borne haute du nombre d'objets en attente (statistiques).
+    count: AtomicU16,
+    offset: usize,
+    /// Encodage des noeuds (voir `NodeCodec`).
+    codec: NodeCodec,
+}
+
+impl RemoteList {
+    /// Pile vide, slab non gelé ; noeuds à `offset` octets dans chaque objet.
+    pub fn with_offset(offset: usize) -> Self {
+        Self {
+            head: AtomicUsize::new(0),
+            count: AtomicU16::new(0),
+            offset,
+            codec: NodeCodec::default(),
+        }
+    }
+
+    /// Fixe l'encodage des noeuds (voir `FreeList::with_codec`).
+    pub fn with_codec(mut self, codec: NodeCodec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Vrai si le slab est gelé (les `push` sont acceptés).
+    pub fn is_frozen(&self) -> bool {
+        self.head.load(Ordering::Acquire) & FROZEN != 0
+    }
+
+    /// Nombre d'objets en attente (approximatif pendant un `push` / `drain_into`).
+    pub fn len(&self) -> usize {
+        self.count.load(Ordering::Relaxed) as usize
+    }
+
+    /// Vrai si aucun objet n'attend.
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire) & !FROZEN == 0
+    }
+
+    /// Gèle le slab : les `push` sont désormais acceptés.
+    pub fn freeze(&self) {
+        debug_assert!(self.is_empty(), "RemoteList::freeze: pile non vide");
+        self.head.store(FROZEN, Ordering::Release);
+    }
+
+    /// Empile `ptr` si le slab est gelé ; retourne `false` sinon (l'objet
+    /// doit alors être libéré par le chemin ordinaire, sous verrou).
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` appartient au slab de cette pile et n'est pas déjà libéré.
+    /// - `ptr + offset` est aligné pour un `FreeNode`.
+    pub unsafe fn push(&self, ptr: NonNull<u8>) -> bool {
+        let mut head = self.head.load(Ordering::Relaxed);
+        if head & FROZEN == 0 {
+            return false;
+        }
+
+        // Compté avant d'être visible : `len` ne sous-estime jamais la pile.
+        self.count.fetch_add(1, Ordering::Relaxed);
+        loop {
+            let next = NonNull::new((head & !FROZEN) as *mut u8);
+            self.codec.check_double_free(next, ptr);
+            self.codec.store(ptr.add(self.offset), next);
+            // Release : le noeud écrit ci-dessus est visible pour `drain_into`.
+            match self.head.compare_exchange_weak(
+                head,
+                ptr.as_ptr() as usize | FROZEN,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return true,
+                Err(cur) if cur & FROZEN == 0 => {
+                    // Dégelé entre-temps.
+                    self.count.fetch_sub(1, Ordering::Relaxed);
+                    return false;
+                }
+                Err(cur) => head = cur,
+            }
+        }
+    }
+
+    /// Prend toute la pile et la verse dans `list` ; retourne le nombre
+    /// d'objets transférés. Le slab reste gelé si `keep_frozen`, sinon il est
+    /// dégelé dans le même échange.
+    ///
+    /// # Safety
+    ///
+    /// - réservé au propriétaire du slab (accès exclusif à `list`).
+    /// - `list` utilise le même `offset` que cette pile.
+    pub unsafe fn drain_into(&self, list: &mut FreeList, keep_frozen: bool) -> usize {
+        let new = if keep_frozen { FROZEN } else { 0 };
+        // Acquire : voit les noeuds écrits par chaque `push`.
+        let head = self.head.swap(new, Ordering::Acquire);
+
+        let mut cur = NonNull::new((head & !FROZEN) as *mut u8);
+        let mut n = 0;
+        while let Some(obj) = cur {
+            // Suivant lu (et vérifié) avant que `push` ne réécrive le noeud.
+            cur = self.codec.load(obj, obj.add(self.offset));
+            list.push(obj);
+            n += 1;
+        }
+        self.count.fetch_sub(n as u16, Ordering::Relaxed);
+        n
+    }
+}
+
+/// Bits de `AtomicFreeList::head`, de poids faible à poids fort : index de
+/// la tête + 1 (0 = liste vide), nombre d'objets libres, état gelé ; les bits
+/// au-dessus forment le compteur de version.
+const IDX_BITS: u32 = (PAGE_SIZE / core::mem::size_of::<FreeNode>()).ilog2() + 1;
+const IDX_MASK: usize = (1 << IDX_BITS) - 1;
+const LEN_ONE: usize = 1 << IDX_BITS;
+const LEN_MASK: usize = IDX_MASK << IDX_BITS;
+const SHARED_FROZEN: usize = 1 << (2 * IDX_BITS);
+const VERSION_ONE: usize = SHARED_FROZEN << 1;
+
+/// Freelist LIFO sans verrou (pile de Treiber), pour un slab partagé entre
+/// threads.
+///
+/// Les noeuds ne sont pas dans les objets : un `pop` concurrent peut lire le
+/// noeud d'un objet qu'un autre thread vient de retirer et remplit déjà. Ils
+/// forment une table d'atomiques (index du suivant + 1), un par objet, placée
+/// entre le header et les objets (voir `nodes_size`).
+///
+/// La tête ne stocke que l'index de l'objet, ce qui laisse de la place pour
+/// le nombre d'objets libres (exact à chaque instant, voir `len`), l'état
+/// gelé et un compteur de version. Chaque `push` / `pop` réussi incrémente ce
+/// dernier, et un CAS sur une tête dont la version a changé échoue : un `pop`
+/// qui aurait lu un suivant périmé (objet retiré puis remis entre-temps :
+/// ABA) recommence. Sur une cible 32 bits, la version n'a que quelques bits.
+pub struct AtomicFreeList {
+    head: AtomicUsize,
+    /// Offset (dans la page) de la table des noeuds.
+    nodes: usize,
+    /// Disposition des objets et encodage des noeuds (voir `NodeCodec`).
+    codec: NodeCodec,
+}
+
+impl AtomicFreeList {
+    /// Liste vide : table des noeuds à `nodes` octets du début de la page,
+    /// objets disposés selon `codec`.
+    pub fn new(nodes: usize, codec: NodeCodec) -> Self {
+        Self {
+            head: AtomicUsize::new(0),
+            nodes,
+            codec,
+        }
+    }
+
+    /// Taille de la table des noeuds pour `capacity` objets.
+    pub const fn nodes_size(capacity: usize) -> usize {
+        capacity * core::mem::size_of::<AtomicU16>()
+    }
+
+    /// Vrai si la liste est vide (photographie).
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Ordering::Acquire) & IDX_MASK == 0
+    }
+
+    /// Nombre d'objets dans la liste (photographie).
+    pub fn len(&self) -> usize {
+        (self.head.load(Ordering::Acquire) & LEN_MASK) >> IDX_BITS
+    }
+
+    /// Vrai si le slab est gelé (`push_frozen` accepté).
+    pub fn is_frozen(&self) -> bool {
+        self.head.load(Ordering::Acquire) & SHARED_FROZEN != 0
+    }
+
+    /// Gèle le slab : `push_frozen` est désormais accepté.
+    pub fn freeze(&self) {
+        self.head.fetch_or(SHARED_FROZEN, Ordering::AcqRel);
+    }
+
+    /// Dégèle le slab ; après l'appel, tout `push_frozen` échoue et `len` ne
+    /// bouge plus que sous l'accès exclusif de l'appelant.
+    pub fn unfreeze(&self) {
+        self.head.fetch_and(!SHARED_FROZEN, Ordering::AcqRel);
+    }
+
+    /// Noeud de l'objet d'index `idx`.
+    ///
+    /// # Safety
+    ///
+    /// - `base` est la page de la liste, vivante, et `idx` < capacité.
+    unsafe fn node(&self, base: NonNull<u8>, idx: usize) -> *mut AtomicU16 {
+        base.as_ptr().add(self.nodes).cast::<AtomicU16>().add(idx)
+    }
+
+    /// Initialise la table des noeuds : la liste contient les objets
+    /// d'index `order`, `order[0]` en tête. À appeler une fois, avant tout
+    /// partage du slab.
+    ///
+    /// # Safety
+    ///
+    /// - `base` est la page de la liste, la table des noeuds y tient et
+    ///   n'est pas encore initialisée.
+    /// - chaque index de `order` est < capacité, sans doublon.
+    pub unsafe fn fill(&self, base: NonNull<u8>, order: &[u16]) {
+        for (k, &idx) in order.iter().enumerate() {
+            let next = order.get(k + 1).map_or(0, |&n| n as usize + 1);
+            let node = self.codec.encode_index(idx as usize, next);
+            self.node(base, idx as usize).write(AtomicU16::new(node));
+        }
+        let head = order.first().map_or(0, |&i| i as usize + 1);
+        self.head.store((order.len() * LEN_ONE) | head, Ordering::Release);
+    }
+
+    /// Ajoute `ptr` en tête (tout thread).
+    ///
+    /// # Safety
+    ///
+    /// - `ptr` est un objet de la page `base`, libre, pas déjà dans la liste.
+    /// - la table des noeuds a été initialisée par `fill`.
+    pub unsafe fn push(&self, base: NonNull<u8>, ptr: NonNull<u8>) {
+        self.push_if(base, ptr, false);
+    }
+
+    /// Comme `push`, seulement si le slab est gelé ; retourne `false` sinon
+    /// (l'objet doit alors être libéré par le chemin ordinaire, sous verrou).
+    ///
+    /// # Safety
+    ///
+    /// Comme `push`.
+    pub unsafe fn push_frozen(&self, base: NonNull<u8>, ptr: NonNull<u8>) -> bool {
+        self.push_if(base, ptr, true)
+    }
+
+    unsafe fn push_if(&self, base: NonNull<u8>, ptr: NonNull<u8>, frozen_only: bool) -> bool {
+        let idx = self.codec.index(base, ptr);
+        let node = &*self.node(base, idx);
+
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            if frozen_only && head & SHARED_FROZEN == 0 {
+                return false;
+            }
+            let top = (head & IDX_MASK).checked_sub(1).map(|i| self.codec.object(base, i));
+            self.codec.check_double_free(top, ptr);
+            node.store(self.codec.encode_index(idx, head & IDX_MASK), Ordering::Relaxed);
+            let new = (head & !IDX_MASK).wrapping_add(VERSION_ONE + LEN_ONE) | (idx + 1);
+            // Release : le noeud est visible pour le `pop` qui lira cette tête.
+            match self
+                .head
+                .compare_exchange_weak(head, new, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => return true,
+                Err(cur) => head = cur,
+            }
+        }
+    }
+
+    /// Retire l'objet de tête (tout thread).
+    ///
+    /// # Safety
+    ///
+    /// - `base` est la page des objets de la liste, vivante.
+    pub unsafe fn pop(&self, base: NonNull<u8>) -> Option<NonNull<u8>> {
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            let pos = head & IDX_MASK;
+            if pos == 0 {
+                return None;
+            }
+            // Peut être périmé si l'objet a été retiré entre-temps : la
+            // version de la tête a alors changé et le CAS échoue.
+            let next = self.codec.decode_index(pos - 1, (*self.node(base, pos - 1)).load(Ordering::Relaxed));
+            let new = (head & !IDX_MASK).wrapping_add(VERSION_ONE).wrapping_sub(LEN_ONE) | next;
+            match self
+                .head
+                .compare_exchange_weak(head, new, Ordering::Acquire, Ordering::Acquire)
+            {
+                Ok(_) => return Some(self.codec.object(base, pos - 1)),
+                Err(cur) => head = cur,
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::*;
+
+    /// Objets de test dans une même page (exigé par `freelist-hardened`).
+    #[repr(C, align(64))]
+    struct Objs([[u64; 2]; 4]);
+
+    #[test]
+    fn remote_list_only_accepts_pushes_while_frozen() {
+        let mut objs = Objs([[0u64; 2]; 4]);
+        let ptrs: [NonNull<u8>; 4] = core::array::from_fn(|i| NonNull::from(&mut objs.0[i]).cast());
+
+        let remote = RemoteList::with_offset(8);
+        assert!(!unsafe { remote.push(ptrs[0]) });
+
+        remote.freeze();
+        for &p in ptrs.iter() {
+            assert!(unsafe { remote.push(p) });
+        }
+        assert_eq!(remote.len(), 4);
+
+        // Vidée en lot, toujours gelée.
+        let mut list = FreeList::with_offset(8);
+        assert_eq!(unsafe { remote.drain_into(&mut list, true) }, 4);
+        assert!(remote.is_frozen() && remote.is_empty());
+        let mut got: [Option<NonNull<u8>>; 4] = [None; 4];
+        for slot in got.iter_mut() {
+            *slot = unsafe { list.pop() };
+        }
+        assert!(unsafe { list.pop() }.is_none());
+        assert!(ptrs.iter().all(|p| got.contains(&Some(*p))));
+
+        // Dégel : le push suivant est refusé.
+        assert!(unsafe { remote.push(ptrs[1]) });
+        assert_eq!(unsafe { remote.drain_into(&mut list, false) }, 1);
+        assert!(!remote.is_frozen());
+        assert!(!unsafe { remote.push(ptrs[2]) });
+        assert_eq!(remote.len(), 0);
+    }
+
+    /// Objets de test : 16 octets, à un offset multiple de 64 dans la page.
+    #[cfg(feature = "freelist-hardened")]
+    const CODEC: NodeCodec = NodeCodec::new(0, 16, PAGE_SIZE / 16, 0x5a5a_5a5a);
+
+    #[test]
+    #[cfg(feature = "freelist-hardened")]
+    fn hardened_nodes_are_encoded() {
+        let mut objs = Objs([[0u64; 2]; 4]);
+        let ptrs: [NonNull<u8>; 4] = core::array::from_fn(|i| NonNull::from(&mut objs.0[i]).cast());
+
+        let mut list = FreeList::with_offset(8).with_codec(CODEC);
+        for &p in ptrs.iter() {
+            unsafe { list.push(p) };
+        }
+        // Aucun noeud ne contient l'adresse en clair de son suivant.
+        for (i, obj) in objs.0.iter().enumerate().skip(1) {
+            assert_ne!(obj[1] as usize, ptrs[i - 1].as_ptr() as usize);
+        }
+        for &p in ptrs.iter().rev() {
+            assert_eq!(unsafe { list.pop() }, Some(p));
+        }
+        assert!(unsafe { list.pop() }.is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "freelist-hardened")]
+    fn hardened_remote_nodes_are_encoded() {
+        let mut objs = Objs([[0u64; 2]; 4]);
+        let ptrs: [NonNull<u8>; 4] = core::array::from_fn(|i| NonNull::from(&mut objs.0[i]).cast());
+
+        let remote = RemoteList::with_offset(8).with_codec(CODEC);
+        remote.freeze();
+        for &p in ptrs.iter() {
+            assert!(unsafe { remote.push(p) });
+        }
+        for (i, obj) in objs.0.iter().enumerate().skip(1) {
+            assert_ne!(obj[1] as usize, ptrs[i - 1].as_ptr() as usize);
+        }
+        let mut list = FreeList::with_offset(8).with_codec(CODEC);
+        assert_eq!(unsafe { remote.drain_into(&mut list, false) }, 4);
+        for _ in 0..4 {
+            assert!(unsafe { list.pop() }.is_some());
+        }
+        assert!(unsafe { list.pop() }.is_none());
+    }
+}
+
+/// Modèles `loom` de `RemoteList` et `AtomicFreeList` : `RUSTFLAGS="--cfg loom" cargo test --release --lib loom`.
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use super::*;
+    use loom::sync::Arc;
+    use loom::thread;
+
+    const OBJS: usize = 3;
+
+    /// Objets factices (8 octets, alignés) ; adresses passées entre threads.
+    fn objects() -> (std::boxed::Box<[u64; OBJS]>, [usize; OBJS]) {
+        let mut buf = std::boxed::Box::new([0u64; OBJS]);
+        let addrs = core::array::from_fn(|i| &mut buf[i] as *mut u64 as usize);
+        (buf, addrs)
+    }
+
+    fn nn(addr: usize) -> NonNull<u8> {
+        NonNull::new(addr as *mut u8).unwrap()
+    }
+
+    /// Vide `list` et vérifie que chaque adresse de `expected` y est une seule fois.
+    fn assert_exactly_once(list: &mut FreeList, expected: &[usize]) {
+        let mut seen = [false; OBJS];
+        while let Some(p) = unsafe { list.pop() } {
+            let i = expected.iter().position(|&a| a == p.as_ptr() as usize).expect("objet inconnu");
+            assert!(!seen[i], "objet vu deux fois");
+            seen[i] = true;
+        }
+        assert!(seen[..expected.len()].iter().all(|&s| s), "objet perdu");
+    }
+
+    #[test]
+    fn loom_producers_push_while_owner_drains() {
+        loom::model(|| {
+            let (_buf, addrs) = objects();
+            let remote = Arc::new(RemoteList::with_offset(0));
+            remote.freeze();
+
+            let producers: std::vec::Vec<_> = addrs[..2]
+                .iter()
+                .map(|&a| {
+                    let remote = remote.clone();
+                    thread::spawn(move || assert!(unsafe { remote.push(nn(a)) }))
+                })
+                .collect();
+
+            // Le propriétaire draine pendant les push.
+            let mut list = FreeList::new();
+            unsafe { remote.drain_into(&mut list, true) };
+
+            for p in producers {
+                p.join().unwrap();
+            }
+            assert!(unsafe { remote.push(nn(addrs[2])) });
+            unsafe { remote.drain_into(&mut list, true) };
+            assert_eq!(remote.len(), 0);
+            assert_exactly_once(&mut list, &addrs);
+        });
+    }
+
+    /// Page factice alignée (les positions de `AtomicFreeList` sont relatives
+    /// à la page) : table des noeuds en tête, objets à partir de `START`.
+    struct Page(NonNull<u8>);
+
+    const START: usize = 512;
+    const STRIDE: usize = 16;
+
+    impl Page {
+        const LAYOUT: core::alloc::Layout =
+            match core::alloc::Layout::from_size_align(PAGE_SIZE, PAGE_SIZE) {
+                Ok(l) => l,
+                Err(_) => panic!(),
+            };
+
+        fn new() -> Self {
+            Self(NonNull::new(unsafe { std::alloc::alloc_zeroed(Self::LAYOUT) }).unwrap())
+        }
+
+        fn obj(&self, i: usize) -> usize {
+            self.0.as_ptr() as usize + START + i * STRIDE
+        }
+
+        /// Liste des objets d'index `order` (`order[0]` en tête).
+        fn list(&self, order: &[u16]) -> AtomicFreeList {
+            assert!(AtomicFreeList::nodes_size(OBJS) <= START);
+            let list = AtomicFreeList::new(0, NodeCodec::new(START, STRIDE, OBJS, 0));
+            unsafe { list.fill(self.0, order) };
+            list
+        }
+    }
+
+    impl Drop for Page {
+        fn drop(&mut self) {
+            unsafe { std::alloc::dealloc(self.0.as_ptr(), Self::LAYOUT) };
+        }
+    }
+
+    #[test]
+    fn loom_atomic_freelist_survives_aba() {
+        loom::model(|| {
+            let page = Page::new();
+            let base = page.0.as_ptr() as usize;
+            // Liste [A, B] (A en tête), C déjà distribué.
+            let list = Arc::new(page.list(&[2, 0, 1]));
+            let c = unsafe { list.pop(nn(base)) }.expect("objet C");
+
+            // Pop isolé, pendant qu'un autre thread retire A, B puis rend C et
+            // A : même tête, même nombre d'objets libres. Sans compteur de
+            // version, le premier pourrait remettre B en tête alors que B est
+            // distribué.
+            let popper = {
+                let list = list.clone();
+                thread::spawn(move || unsafe { list.pop(nn(base)) }.map(|p| p.as_ptr() as usize))
+            };
+            let a = unsafe { list.pop(nn(base)) };
+            let b = unsafe { list.pop(nn(base)) };
+            unsafe { list.push(nn(base), c) };
+            if let Some(a) = a {
+                unsafe { list.push(nn(base), a) };
+            }
+            let popped = popper.join().unwrap();
+
+            // Objets détenus + objets restants : chacun exactement une fois.
+            let mut all = std::vec::Vec::new();
+            all.extend(popped);
+            all.extend(b.map(|p| p.as_ptr() as usize));
+            while let Some(p) = unsafe { list.pop(nn(base)) } {
+                all.push(p.as_ptr() as usize);
+            }
+            all.sort_unstable();
+            assert_eq!(all, [page.obj(0), page.obj(1), page.obj(2)]);
+        });
+    }
+
+    #[test]
+    fn loom_atomic_freelist_concurrent_push_pop() {
+        loom::model(|| {
+            let page = Page::new();
+            let base = page.0.as_ptr() as usize;
+            let list = Arc::new(page.list(&[1, 0]));
+            let obj = unsafe { list.pop(nn(base)) }.expect("objet B");
+
+            // Un thread rend un objet pendant que l'autre en prend un.
+            let pusher = {
+                let list = list.clone();
+                let obj = obj.as_ptr() as usize;
+                thread::spawn(move || unsafe { list.push(nn(base), nn(obj)) })
+            };
+            let got = unsafe { list.pop(nn(base)) }.expect("au moins un objet libre");
+            pusher.join().unwrap();
+
+            let rest = unsafe { list.pop(nn(base)) }.expect("objet restant");
+            assert!(unsafe { list.pop(nn(base)) }.is_none());
+            let mut all = [got.as_ptr() as usize, rest.as_ptr() as usize];
+            all.sort_unstable();
+            assert_eq!(all, [page.obj(0), page.obj(1)]);
+        });
+    }
+
+    #[test]
+    fn loom_popped_objects_are_exclusive() {
+        use loom::cell::UnsafeCell;
+
+        loom::model(|| {
+            let page = Page::new();
+            let base = page.0.as_ptr() as usize;
+            let list = Arc::new(page.list(&[0, 1]));
+            // Contenu des objets, suivi par loom : deux threads qui écriraient
+            // dans le même objet en même temps seraient signalés.
+            let objs: Arc<[UnsafeCell<u64>; 2]> = Arc::new([UnsafeCell::new(0), UnsafeCell::new(0)]);
+
+            let workers: std::vec::Vec<_> = (1..=2u64)
+                .map(|id| {
+                    let (list, objs) = (list.clone(), objs.clone());
+                    thread::spawn(move || {
+                        // Un `pop` peut lire le noeud d'un objet qu'un autre
+                        // thread vient de prendre et remplit : noeud hors objet.
+                        let Some(p) = (unsafe { list.pop(nn(base)) }) else { return };
+                        let i = (p.as_ptr() as usize - base - START) / STRIDE;
+                        objs[i].with_mut(|v| unsafe { *v = id });
+                        unsafe { list.push(nn(base), p) };
+                    })
+                })
+                .collect();
+            for w in workers {
+                w.join().unwrap();
+            }
+
+            assert_eq!(list.len(), 2);
+            let mut all = [0; 2];
+            for slot in all.iter_mut() {
+                *slot = unsafe { list.pop(nn(base)) }.expect("objet").as_ptr() as usize;
+            }
+            all.sort_unstable();
+            assert_eq!(all, [page.obj(0), page.obj(1)]);
+        });
+    }
+
+    #[test]
+    fn loom_shared_push_racing_unfreeze_is_never_lost() {
+        loom::model(|| {
+            let page = Page::new();
+            let base = page.0.as_ptr() as usize;
+            let list = Arc::new(page.list(&[0]));
+            list.freeze();
+            let obj = unsafe { list.pop(nn(base)) }.expect("objet").as_ptr() as usize;
+
+            // Libération distante pendant que le propriétaire dégèle.
+            let remote = {
+                let list = list.clone();
+                thread::spawn(move || unsafe { list.push_frozen(nn(base), nn(obj)) })
+            };
+            list.unfreeze();
+            let len = list.len();
+            let pushed = remote.join().unwrap();
+
+            // Le nombre d'objets libres vu au dégel est définitif.
+            assert_eq!(len, pushed as usize);
+            assert_eq!(list.len(), len);
+            assert!(!list.is_frozen());
+        });
+    }
+
+    #[test]
+    fn loom_push_racing_unfreeze_is_never_lost() {
+        loom::model(|| {
+            let (_buf, addrs) = objects();
+            let remote = Arc::new(RemoteList::with_offset(0));
+            remote.freeze();
+
+            let producer = {
+                let remote = remote.clone();
+                let a = addrs[0];
+                thread::spawn(move || unsafe { remote.push(nn(a)) })
+            };
+
+            let mut list = FreeList::new();
+            let drained = unsafe { remote.drain_into(&mut list, false) };
+            let pushed = producer.join().unwrap();
+
+            // Soit le push a eu lieu avant le dégel (et l'objet a été drainé),
+            // soit il a été refusé et le producteur garde l'objet.
+            assert_eq!(drained, pushed as usize);
+            if !pushed {
+                unsafe { list.push(nn(addrs[0])) };
+            }
+            assert!(!remote.is_frozen() && remote.is_empty());
+            assert_eq!(remote.len(), 0);
+            assert_exactly_once(&mut list, &addrs[..1]);
+        });
     }
 }
@@ -0,0 +1,80 @@
+//! Adaptateur `GlobalAlloc` : permet d'installer le `SlabAllocator`
+//! comme `#[global_allocator]`.
+//!
+//! `SlabAllocator::alloc`/`dealloc` prennent `&mut self` ; `GlobalAlloc` ne
+//! donne que `&self`. On place donc l'allocateur derrière un spinlock.
+
+use core::alloc::{GlobalAlloc, Layout};
+
+use crate::allocator::SlabAllocator;
+use crate::page_provider::PageProvider;
+use crate::size_class::SizeClasses;
+use crate::sync::{SpinLock, SpinLockGuard};
+
+/// `SlabAllocator` protégé par un spinlock, utilisable dans un `static`.
+///
+/// ```ignore
+/// #[global_allocator]
+/// static GLOBAL: LockedSlabAllocator<StaticPageProvider<256>> =
+///     LockedSlabAllocator::new(StaticPageProvider::new());
+/// ```
+pub struct LockedSlabAllocator<P: PageProvider, const N: usize = 9> {
+    inner: SpinLock<SlabAllocator<P, N>>,
+}
+
+impl<P: PageProvider> LockedSlabAllocator<P> {
+    /// Constructeur `const` : utilisable pour initialiser un `static`.
+    pub const fn new(provider: P) -> Self {
+        Self {
+            inner: SpinLock::new(SlabAllocator::new(provider)),
+        }
+    }
+}
+
+impl<P: PageProvider> LockedSlabAllocator<P, 26> {
+    /// Variante `const` avec la table fine (voir `SlabAllocator::new_fine`).
+    pub const fn new_fine(provider: P) -> Self {
+        Self {
+            inner: SpinLock::new(SlabAllocator::new_fine(provider)),
+        }
+    }
+}
+
+impl<P: PageProvider, const N: usize> LockedSlabAllocator<P, N> {
+    /// Variante avec une table de size classes personnalisée (aussi `const`).
+    pub const fn with_classes(provider: P, classes: SizeClasses<N>) -> Self {
+        Self {
+            inner: SpinLock::new(SlabAllocator::with_classes(provider, classes)),
+        }
+    }
+
+    /// Prend le lock et donne accès à l'allocateur sous-jacent.
+    pub fn lock(&self) -> SpinLockGuard<'_, SlabAllocator<P, N>> {
+        self.inner.lock()
+    }
+}
+
+// SAFETY:
+// - `alloc` renvoie soit null, soit un bloc respectant `layout` (routage par size class).
+// - `dealloc` reçoit un ptr/layout issus de `alloc` (contrat de GlobalAlloc),
+//   ce qui satisfait les préconditions de `SlabAllocator::dealloc`.
+// - le spinlock sérialise tous les accès à l'allocateur.
+unsafe impl<P: PageProvider + Send, const N: usize> GlobalAlloc for LockedSlabAllocator<P, N> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.inner.lock().alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.inner.lock().alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: précondition de GlobalAlloc::dealloc (ptr issu de alloc(layout)).
+        unsafe { self.inner.lock().dealloc(ptr, layout) };
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // SAFETY: précondition de GlobalAlloc::realloc (ptr issu de alloc(layout)).
+        unsafe { self.inner.lock().realloc(ptr, layout, new_size) }
+    }
+}
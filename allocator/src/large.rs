@@ -0,0 +1,192 @@
+//! Chemin "grosses allocations" (> plus grande size class).
+//!
+//! Chaque allocation est une suite de pages contiguës demandée au provider.
+//! Pour pouvoir la rendre au `dealloc`, on garde un enregistrement
+//! `(ptr, pages)` dans une liste intrusive ; les enregistrements eux-mêmes
+//! sont alloués dans un petit `Cache` interne (pas de heap externe).
+
+use core::mem;
+use core::ptr::NonNull;
+
+use crate::cache::Cache;
+use crate::error::AllocError;
+use crate::page_provider::{PageProvider, PAGE_SIZE};
+
+/// Métadonnées d'une allocation multi-pages.
+#[repr(C)]
+struct LargeRecord {
+    ptr: NonNull<u8>,
+    pages: usize,
+    next: Option<NonNull<LargeRecord>>,
+}
+
+/// Ensemble des allocations multi-pages vivantes.
+pub struct LargeAllocs {
+    records: Cache,
+    head: Option<NonNull<LargeRecord>>,
+}
+
+impl LargeAllocs {
+    pub const fn new() -> Self {
+        Self {
+            records: Cache::new(mem::size_of::<LargeRecord>(), mem::align_of::<LargeRecord>()),
+            head: None,
+        }
+    }
+
+    /// Nombre de pages nécessaires pour `size` bytes.
+    #[inline]
+    pub fn pages_for(size: usize) -> usize {
+        size.max(1).div_ceil(PAGE_SIZE)
+    }
+
+    /// Rend au provider les pages vides du cache de métadonnées.
+    pub fn shrink<P: PageProvider>(&mut self, provider: &mut P) -> usize {
+        self.records.shrink(provider)
+    }
+
+    /// Nombre de grosses allocations vivantes.
+    pub fn live(&self) -> usize {
+        let mut n = 0;
+        let mut cur = self.head;
+        while let Some(rec) = cur {
+            n += 1;
+            // SAFETY: la liste ne contient que des records initialisés par `alloc`.
+            cur = unsafe { (*rec.as_ptr()).next };
+        }
+        n
+    }
+
+    /// Rend toutes les pages (données et métadonnées) au provider.
+    ///
+    /// Retourne le nombre de grosses allocations encore vivantes.
+    ///
+    /// # Safety
+    /// - `provider` doit être celui qui a fourni les pages.
+    /// - aucune grosse allocation ne doit être utilisée après l'appel.
+    pub unsafe fn release_all<P: PageProvider>(&mut self, provider: &mut P) -> usize {
+        let mut live = 0;
+        while let Some(rec) = self.head {
+            // SAFETY: la liste ne contient que des records initialisés par `alloc`.
+            let r = unsafe { rec.as_ptr().read() };
+            self.head = r.next;
+            provider.dealloc_pages(r.ptr, r.pages);
+            live += 1;
+        }
+        // SAFETY: plus aucun record n'est référencé (liste vidée).
+        unsafe { self.records.release_all(provider) };
+        live
+    }
+
+    /// Comme `alloc`, mais les `size` premiers bytes sont à zéro (effacés
+    /// seulement si le provider ne fournit pas déjà des pages à zéro).
+    pub fn alloc_zeroed<P: PageProvider>(
+        &mut self,
+        size: usize,
+        provider: &mut P,
+    ) -> Result<NonNull<u8>, AllocError> {
+        let ptr = self.alloc(size, provider)?;
+        if !P::ZEROES_PAGES {
+            // SAFETY: bloc fraîchement alloué d'au moins `size` bytes.
+            unsafe { ptr.as_ptr().write_bytes(0, size) };
+        }
+        Ok(ptr)
+    }
+
+    /// Alloue `size` bytes sous forme de pages contiguës (alignées sur PAGE_SIZE).
+    pub fn alloc<P: PageProvider>(&mut self, size: usize, provider: &mut P) -> Result<NonNull<u8>, AllocError> {
+        let pages = Self::pages_for(size);
+
+        let rec = self.records.try_alloc(provider)?.cast::<LargeRecord>();
+        let Some(ptr) = provider.alloc_pages(pages) else {
+            // SAFETY: `rec` vient d'être alloué par ce cache et n'est pas encore publié.
+            unsafe { self.records.dealloc(rec.cast(), provider) };
+            return Err(AllocError::OutOfPages);
+        };
+
+        // SAFETY:
+        // - `rec` est un objet frais du cache `records`, dimensionné/aligné pour LargeRecord.
+        // - personne d'autre ne le référence encore.
+        unsafe {
+            rec.as_ptr().write(LargeRecord {
+                ptr,
+                pages,
+                next: self.head,
+            });
+        }
+        self.head = Some(rec);
+
+        Ok(ptr)
+    }
+
+    /// Vrai si `ptr` pointe dans une grosse allocation vivante (n'importe
+    /// où dans ses pages) ou dans une page de métadonnées.
+    pub fn owns(&self, ptr: NonNull<u8>) -> bool {
+        let addr = ptr.as_ptr() as usize;
+        let mut cur = self.head;
+        while let Some(rec) = cur {
+            // SAFETY: la liste ne contient que des records initialisés par `alloc`.
+            let r = unsafe { rec.as_ptr().read() };
+            let start = r.ptr.as_ptr() as usize;
+            if addr >= start && addr < start + r.pages * PAGE_SIZE {
+                return true;
+            }
+            cur = r.next;
+        }
+        self.records.owns(ptr)
+    }
+
+    /// Nombre de pages de l'allocation qui commence à `ptr` (None si inconnue).
+    pub fn pages_of(&self, ptr: NonNull<u8>) -> Option<usize> {
+        let mut cur = self.head;
+        while let Some(rec) = cur {
+            // SAFETY: la liste ne contient que des records initialisés par `alloc`.
+            let r = unsafe { rec.as_ptr().read() };
+            if r.ptr == ptr {
+                return Some(r.pages);
+            }
+            cur = r.next;
+        }
+        None
+    }
+
+    /// Rend au provider l'allocation qui commence à `ptr`.
+    ///
+    /// Retourne `false` si `ptr` n'est pas une grosse allocation connue.
+    ///
+    /// # Safety
+    /// - si `ptr` est connu, il ne doit plus être utilisé après l'appel (pas de double free).
+    pub unsafe fn dealloc<P: PageProvider>(&mut self, ptr: NonNull<u8>, provider: &mut P) -> bool {
+        let mut prev: Option<NonNull<LargeRecord>> = None;
+        let mut cur = self.head;
+
+        while let Some(rec) = cur {
+            // SAFETY: la liste ne contient que des records initialisés par `alloc`.
+            let r = unsafe { rec.as_ptr().read() };
+
+            if r.ptr == ptr {
+                match prev {
+                    // SAFETY: `p` est un record vivant de la liste.
+                    Some(p) => unsafe { (*p.as_ptr()).next = r.next },
+                    None => self.head = r.next,
+                }
+
+                provider.dealloc_pages(r.ptr, r.pages);
+                // SAFETY: `rec` provient de `records.alloc` et vient d'être retiré de la liste.
+                unsafe { self.records.dealloc(rec.cast(), provider) };
+                return true;
+            }
+
+            prev = cur;
+            cur = r.next;
+        }
+
+        false
+    }
+}
+
+impl Default for LargeAllocs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,28 +1,49 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[cfg(any(test, feature = "test-provider"))]
+#[cfg(any(test, feature = "std"))]
 extern crate std;
 
 pub mod page_provider;
 pub mod cache;
 pub mod allocator;
+pub mod error;
 pub mod freelist;
+pub mod large;
+pub mod named_cache;
+pub mod object_cache;
 pub mod slab;
+pub mod size_class;
+pub mod sync;
+pub mod global;
+pub mod concurrent;
+pub mod percpu;
+pub mod magazine;
+#[cfg(feature = "allocator-api2")]
+pub mod allocator_api;
 
 // Re-export des interfaces publiques (pratique pour les tests et l'usage)
 pub use page_provider::PageProvider;
 pub use cache::Cache;
+pub use error::AllocError;
 pub use crate::allocator::SlabAllocator;
+pub use global::LockedSlabAllocator;
+pub use concurrent::ConcurrentSlabAllocator;
+pub use percpu::PerCpuSlabAllocator;
+pub use magazine::MagazineCache;
+pub use size_class::{SizeClassError, SizeClasses};
+pub use named_cache::{CacheError, CacheFlags, CacheHandle};
+pub use object_cache::{ObjectCache, SlabBox};
 
 /// Taille d'une page (backend). Fixée pour le projet.
 pub const PAGE_SIZE: usize = 4096;
 
-/// Tailles de caches supportées (size classes)
-pub const SIZE_CLASSES: &[usize] = &[8, 16, 32, 64, 128, 256, 512, 1024, 2048];
+/// Tailles de caches par défaut (size classes), voir `size_class`.
+pub const SIZE_CLASSES: &[usize] = &size_class::DEFAULT_CLASS_SIZES;
 
-/// Petite fonction utilitaire: renvoie l'index de cache correspondant à une taille.
+/// Petite fonction utilitaire: renvoie l'index de cache (table par défaut)
+/// correspondant à une taille.
 pub fn size_class_index(size: usize) -> Option<usize> {
-    SIZE_CLASSES.iter().position(|&c| size <= c)
+    size_class::DEFAULT_SIZE_CLASSES.index_for(size)
 }
 
 #[cfg(test)]
@@ -60,11 +81,31 @@ mod tests {
     }
 
     #[test]
-    fn unsupported_size_returns_null() {
+    fn large_size_uses_page_runs() {
         let mut a = make_allocator();
 
         let layout = Layout::from_size_align(4096 * 2, 8).unwrap();
         let p = a.alloc(layout);
+        assert!(!p.is_null());
+        assert_eq!((p as usize) % crate::PAGE_SIZE, 0);
+
+        // Toute la zone est utilisable.
+        unsafe { core::ptr::write_bytes(p, 0x5A, layout.size()) };
+        unsafe { a.dealloc(p, layout) };
+
+        // Les pages sont rendues : on retrouve le même bloc.
+        let q = a.alloc(layout);
+        #[cfg(not(miri))]
+        assert_eq!(p, q);
+        unsafe { a.dealloc(q, layout) };
+    }
+
+    #[test]
+    fn alignment_above_page_returns_null() {
+        let mut a = make_allocator();
+
+        let layout = Layout::from_size_align(4096 * 2, 4096 * 2).unwrap();
+        let p = a.alloc(layout);
         assert!(p.is_null());
     }
 
@@ -75,8 +116,10 @@ mod tests {
         let layout = Layout::from_size_align(24, 64).unwrap();
         let p = a.alloc(layout);
 
-        // On refuse car align > size class (allocateur minimal)
-        assert!(p.is_null());
+        // align > size class : routé vers la classe 64
+        assert!(!p.is_null());
+        assert_eq!((p as usize) % 64, 0);
+        unsafe { a.dealloc(p, layout) };
     }
 }
 
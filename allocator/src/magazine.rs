@@ -0,0 +1,536 @@
+//! Couche magazines / dépôt devant un `Cache` (Bonwick, "Magazines and
+//! Vmem", 2001).
+//!
+//! Un magazine est un petit tableau de pointeurs d'objets déjà pris dans le
+//! cache ("rounds"). Chaque CPU en tient deux (`loaded` et `previous`) :
+//! - `alloc` dépile de `loaded`, sinon échange avec `previous` s'il est plein ;
+//! - `free` empile dans `loaded`, sinon échange avec `previous` s'il est vide.
+//!
+//! Quand les deux sont épuisés (ou pleins), le CPU échange un magazine entier
+//! avec le dépôt partagé (listes de magazines pleins / vides). Le `Cache`
+//! n'est touché que si le dépôt n'a rien à offrir : au plus une opération de
+//! liste de slabs toutes les `magazine_size` allocations, et un chemin rapide
+//! de durée bornée (quelques échanges de pointeurs sous le verrou du CPU).
+//!
+//! Les magazines eux-mêmes sont alloués dans un `Cache` interne (pas de heap
+//! externe) ; passer un magazine d'un CPU au dépôt est un échange de pointeur.
+//!
+//! Ordre de verrouillage : CPU, puis dépôt, puis cache, puis provider.
+
+use core::mem;
+use core::ops::DerefMut;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::cache::Cache;
+use crate::concurrent::SharedProvider;
+use crate::error::AllocError;
+use crate::page_provider::PageProvider;
+use crate::percpu::default_cpu_id;
+use crate::slab::Slab;
+use crate::sync::Lock;
+
+/// Magazine : pile de `rounds` objets, chaînable dans les listes du dépôt.
+#[repr(C)]
+struct Magazine<const M: usize> {
+    next: *mut Magazine<M>,
+    rounds: usize,
+    objs: [*mut u8; M],
+}
+
+/// Magazines tenus par un CPU (`null` : pas encore de magazine).
+struct CpuMagazines<const M: usize> {
+    loaded: *mut Magazine<M>,
+    previous: *mut Magazine<M>,
+}
+
+/// Dépôt partagé : magazines pleins et vides, et leur stockage.
+struct Depot<const M: usize> {
+    full: *mut Magazine<M>,
+    full_count: usize,
+    empty: *mut Magazine<M>,
+    /// Cache des magazines eux-mêmes.
+    mags: Cache,
+}
+
+/// Nombre de magazines pleins gardés par défaut dans le dépôt.
+pub const DEFAULT_DEPOT_LIMIT: usize = 8;
+
+/// Cache d'objets avec magazines par CPU devant un `Cache` partagé.
+///
+/// `M` est la capacité d'un magazine ; le nombre de rounds réellement
+/// utilisés se règle à chaud (`set_magazine_size`, au plus `M`).
+///
+/// ```ignore
+/// static INODES: MagazineCache<StaticPageProvider<256>, 4> =
+///     MagazineCache::new(Cache::new(200, 8), StaticPageProvider::new());
+/// ```
+pub struct MagazineCache<P: PageProvider, const CPUS: usize, const M: usize = 16> {
+    cpus: [Lock<CpuMagazines<M>>; CPUS],
+    depot: Lock<Depot<M>>,
+    cache: Lock<Cache>,
+    provider: Lock<P>,
+    magazine_size: AtomicUsize,
+    depot_limit: AtomicUsize,
+    cpu_id: fn() -> usize,
+}
+
+// SAFETY: les objets et magazines sont dans des pages possédées par cet
+// allocateur ; chaque structure qui les référence est protégée par son verrou.
+unsafe impl<P: PageProvider + Send, const CPUS: usize, const M: usize> Send for MagazineCache<P, CPUS, M> {}
+// SAFETY: idem, tout accès partagé passe par un verrou.
+unsafe impl<P: PageProvider + Send, const CPUS: usize, const M: usize> Sync for MagazineCache<P, CPUS, M> {}
+
+impl<P: PageProvider, const CPUS: usize, const M: usize> MagazineCache<P, CPUS, M> {
+    /// Place des magazines devant `cache`, dont les pages viennent de `provider`.
+    pub const fn new(cache: Cache, provider: P) -> Self {
+        assert!(CPUS > 0, "MagazineCache: au moins un CPU");
+        assert!(M > 0, "MagazineCache: magazines vides");
+        let mag_size = mem::size_of::<Magazine<M>>();
+        let mag_align = mem::align_of::<Magazine<M>>();
+        assert!(Slab::fits(mag_size, mag_align), "MagazineCache: magazine plus grand qu'une page");
+
+        Self {
+            cpus: [const {
+                Lock::new(CpuMagazines {
+                    loaded: ptr::null_mut(),
+                    previous: ptr::null_mut(),
+                })
+            }; CPUS],
+            depot: Lock::new(Depot {
+                full: ptr::null_mut(),
+                full_count: 0,
+                empty: ptr::null_mut(),
+                mags: Cache::new(mag_size, mag_align),
+            }),
+            cache: Lock::new(cache),
+            provider: Lock::new(provider),
+            magazine_size: AtomicUsize::new(M),
+            depot_limit: AtomicUsize::new(DEFAULT_DEPOT_LIMIT),
+            cpu_id: default_cpu_id,
+        }
+    }
+
+    /// Remplace la fonction qui donne le CPU courant (index réduit modulo `CPUS`).
+    pub const fn with_cpu_id(mut self, cpu_id: fn() -> usize) -> Self {
+        self.cpu_id = cpu_id;
+        self
+    }
+
+    /// Nombre de rounds par magazine (1..=`M`). Les magazines déjà plus
+    /// remplis se vident normalement.
+    pub fn set_magazine_size(&self, rounds: usize) {
+        self.magazine_size.store(rounds.clamp(1, M), Ordering::Relaxed);
+    }
+
+    /// Nombre de rounds par magazine.
+    pub fn magazine_size(&self) -> usize {
+        self.magazine_size.load(Ordering::Relaxed)
+    }
+
+    /// Nombre maximal de magazines pleins gardés dans le dépôt ; au-delà, un
+    /// magazine rendu est vidé dans le cache.
+    pub fn set_depot_limit(&self, limit: usize) {
+        self.depot_limit.store(limit, Ordering::Relaxed);
+    }
+
+    /// Nombre de magazines pleins actuellement dans le dépôt.
+    pub fn depot_full_magazines(&self) -> usize {
+        self.depot.lock().full_count
+    }
+
+    fn shared_provider(&self) -> SharedProvider<'_, P> {
+        SharedProvider::new(&self.provider)
+    }
+
+    /// Alloue un objet sur le CPU courant ; null en cas d'échec.
+    pub fn alloc(&self) -> *mut u8 {
+        self.alloc_on((self.cpu_id)())
+    }
+
+    /// Comme `alloc`, sur le CPU `cpu`.
+    pub fn alloc_on(&self, cpu: usize) -> *mut u8 {
+        self.try_alloc_on(cpu).map_or(ptr::null_mut(), NonNull::as_ptr)
+    }
+
+    /// Comme `alloc_on`, avec la raison de l'échec.
+    pub fn try_alloc_on(&self, cpu: usize) -> Result<NonNull<u8>, AllocError> {
+        let mut guard = self.cpus[cpu % CPUS].lock();
+        let mags = &mut *guard;
+        // SAFETY: magazines de ce CPU, protégés par son verrou.
+        unsafe {
+            if let Some(obj) = pop(mags.loaded) {
+                return Ok(obj);
+            }
+            if rounds(mags.previous) > 0 {
+                mem::swap(&mut mags.loaded, &mut mags.previous);
+                return Ok(pop(mags.loaded).expect("magazine plein"));
+            }
+
+            // Magazine plein du dépôt : `previous` (vide) y retourne.
+            let mut depot = self.depot.lock();
+            if let Some(full) = depot.take_full() {
+                depot.put_empty(mags.previous);
+                mags.previous = mags.loaded;
+                mags.loaded = full;
+                drop(depot);
+                return Ok(pop(mags.loaded).expect("magazine plein"));
+            }
+        }
+
+        // Rien en réserve : directement au cache.
+        let mut provider = self.shared_provider();
+        self.cache.lock().try_alloc(&mut provider)
+    }
+
+    /// Libère un objet sur le CPU courant.
+    ///
+    /// # Safety
+    /// - `ptr` vient d'un `alloc` de CET allocateur, non encore libéré.
+    pub unsafe fn free(&self, ptr: *mut u8) {
+        // SAFETY: préconditions identiques.
+        unsafe { self.free_on((self.cpu_id)(), ptr) };
+    }
+
+    /// Comme `free`, sur le CPU `cpu` (quel que soit le CPU qui a alloué).
+    ///
+    /// # Safety
+    /// Mêmes préconditions que `free`.
+    pub unsafe fn free_on(&self, cpu: usize, ptr: *mut u8) {
+        let Some(obj) = NonNull::new(ptr) else { return };
+        let size = self.magazine_size();
+
+        let mut guard = self.cpus[cpu % CPUS].lock();
+        let mags = &mut *guard;
+        // SAFETY: magazines de ce CPU (verrou) ; `obj` est un objet alloué du cache.
+        unsafe {
+            if push(mags.loaded, obj, size) {
+                return;
+            }
+            if !mags.previous.is_null() && rounds(mags.previous) == 0 {
+                mem::swap(&mut mags.loaded, &mut mags.previous);
+                push(mags.loaded, obj, size);
+                return;
+            }
+
+            // Magazine vide du dépôt : `previous` (plein) y retourne.
+            let mut depot = self.depot.lock();
+            let mut provider = self.shared_provider();
+            if let Some(empty) = depot.take_empty(&mut provider) {
+                if !mags.previous.is_null() {
+                    if depot.full_count < self.depot_limit.load(Ordering::Relaxed) {
+                        depot.put_full(mags.previous);
+                    } else {
+                        // Dépôt saturé : le magazine est vidé dans le cache.
+                        self.drain_into_cache(mags.previous, &mut provider);
+                        depot.put_empty(mags.previous);
+                    }
+                }
+                mags.previous = mags.loaded;
+                mags.loaded = empty;
+                drop(depot);
+                push(mags.loaded, obj, size);
+                return;
+            }
+        }
+
+        // Pas de magazine disponible (plus de pages) : directement au cache.
+        let mut provider = self.shared_provider();
+        // SAFETY: `obj` vient de ce cache, pages issues de `provider`.
+        let res = unsafe { self.cache.lock().try_dealloc(obj, &mut provider) };
+        debug_assert!(res.is_ok(), "free: {res:?}");
+    }
+
+    /// Rend au cache les rounds d'un magazine (qui reste, vide, à l'appelant).
+    ///
+    /// # Safety
+    /// `mag` est un magazine valide détenu par l'appelant.
+    unsafe fn drain_into_cache(&self, mag: *mut Magazine<M>, provider: &mut SharedProvider<'_, P>) {
+        let mut cache = self.cache.lock();
+        // SAFETY: rounds = objets alloués de ce cache (précondition).
+        while let Some(obj) = unsafe { pop(mag) } {
+            // SAFETY: idem.
+            let res = unsafe { cache.try_dealloc(obj, provider) };
+            debug_assert!(res.is_ok(), "magazine: {res:?}");
+        }
+    }
+
+    /// Vide les magazines de `cpu` dans le cache et rend leur stockage.
+    pub fn flush(&self, cpu: usize) {
+        let mut guard = self.cpus[cpu % CPUS].lock();
+        let mags = &mut *guard;
+        let mut provider = self.shared_provider();
+        for mag in [mem::replace(&mut mags.loaded, ptr::null_mut()), mem::replace(&mut mags.previous, ptr::null_mut())] {
+            if !mag.is_null() {
+                // SAFETY: magazine retiré de ce CPU, sous son verrou.
+                unsafe {
+                    self.drain_into_cache(mag, &mut provider);
+                    self.depot.lock().free_magazine(mag, &mut provider);
+                }
+            }
+        }
+    }
+
+    /// Vide tous les magazines (CPU et dépôt) puis rend au provider les
+    /// slabs vides ; retourne le nombre de pages rendues.
+    pub fn shrink(&self) -> usize {
+        for cpu in 0..CPUS {
+            self.flush(cpu);
+        }
+
+        let mut provider = self.shared_provider();
+        let mut depot = self.depot.lock();
+        // SAFETY: magazines du dépôt, sous son verrou.
+        unsafe {
+            while let Some(mag) = depot.take_full() {
+                self.drain_into_cache(mag, &mut provider);
+                depot.free_magazine(mag, &mut provider);
+            }
+            while !depot.empty.is_null() {
+                let mag = depot.empty;
+                depot.empty = (*mag).next;
+                depot.free_magazine(mag, &mut provider);
+            }
+        }
+        let released = depot.mags.shrink(&mut provider);
+        drop(depot);
+
+        released + self.cache.lock().shrink(&mut provider)
+    }
+
+    /// Nombre d'objets alloués aux utilisateurs (hors rounds des magazines).
+    ///
+    /// Photographie non atomique : les CPU sont lus l'un après l'autre.
+    pub fn live_objects(&self) -> usize {
+        let mut cached = 0;
+        for cpu in self.cpus.iter() {
+            let mags = cpu.lock();
+            // SAFETY: magazines de ce CPU, sous son verrou.
+            cached += unsafe { rounds(mags.loaded) + rounds(mags.previous) };
+        }
+        let depot = self.depot.lock();
+        let mut cur = depot.full;
+        while !cur.is_null() {
+            // SAFETY: liste du dépôt, sous son verrou.
+            unsafe {
+                cached += (*cur).rounds;
+                cur = (*cur).next;
+            }
+        }
+        drop(depot);
+
+        self.cache.lock().live_objects().saturating_sub(cached)
+    }
+
+    /// Verrouille le `Cache` sous-jacent (statistiques, réglages).
+    pub fn lock_cache(&self) -> impl DerefMut<Target = Cache> + '_ {
+        self.cache.lock()
+    }
+
+    /// Verrouille le provider (statistiques). Ne pas allouer depuis cet
+    /// allocateur tant que la garde est vivante.
+    pub fn lock_provider(&self) -> impl DerefMut<Target = P> + '_ {
+        self.provider.lock()
+    }
+}
+
+/// Démontage : magazines vidés, puis toutes les pages rendues au provider.
+impl<P: PageProvider, const CPUS: usize, const M: usize> Drop for MagazineCache<P, CPUS, M> {
+    fn drop(&mut self) {
+        self.shrink();
+        let provider = self.provider.get_mut();
+        // SAFETY: l'allocateur est détruit => plus aucun objet utilisable ;
+        // les pages viennent de `provider`.
+        unsafe {
+            self.cache.get_mut().release_all(provider);
+            self.depot.get_mut().mags.release_all(provider);
+        }
+    }
+}
+
+impl<const M: usize> Depot<M> {
+    /// Retire un magazine plein.
+    fn take_full(&mut self) -> Option<*mut Magazine<M>> {
+        let mag = self.full;
+        if mag.is_null() {
+            return None;
+        }
+        // SAFETY: la liste ne contient que des magazines valides.
+        self.full = unsafe { (*mag).next };
+        self.full_count -= 1;
+        Some(mag)
+    }
+
+    /// Retire un magazine vide (en alloue un si la liste est vide).
+    fn take_empty<P: PageProvider>(&mut self, provider: &mut P) -> Option<*mut Magazine<M>> {
+        let mag = self.empty;
+        if !mag.is_null() {
+            // SAFETY: la liste ne contient que des magazines valides.
+            self.empty = unsafe { (*mag).next };
+            return Some(mag);
+        }
+
+        let mag = self.mags.try_alloc(provider).ok()?.as_ptr().cast::<Magazine<M>>();
+        // SAFETY: objet du cache des magazines : taille et alignement de `Magazine<M>`.
+        unsafe {
+            ptr::addr_of_mut!((*mag).next).write(ptr::null_mut());
+            ptr::addr_of_mut!((*mag).rounds).write(0);
+        }
+        Some(mag)
+    }
+
+    /// Range un magazine plein.
+    ///
+    /// # Safety
+    /// `mag` est un magazine valide qui n'est plus tenu par un CPU.
+    unsafe fn put_full(&mut self, mag: *mut Magazine<M>) {
+        (*mag).next = self.full;
+        self.full = mag;
+        self.full_count += 1;
+    }
+
+    /// Range un magazine vide (ignore `null`).
+    ///
+    /// # Safety
+    /// `mag` est null ou un magazine vide qui n'est plus tenu par un CPU.
+    unsafe fn put_empty(&mut self, mag: *mut Magazine<M>) {
+        if mag.is_null() {
+            return;
+        }
+        debug_assert_eq!((*mag).rounds, 0);
+        (*mag).next = self.empty;
+        self.empty = mag;
+    }
+
+    /// Rend le stockage d'un magazine vide à son cache.
+    ///
+    /// # Safety
+    /// `mag` est un magazine vide, hors de toute liste, venant de `mags`.
+    unsafe fn free_magazine<P: PageProvider>(&mut self, mag: *mut Magazine<M>, provider: &mut P) {
+        debug_assert_eq!((*mag).rounds, 0);
+        // SAFETY: `mag` vient de `self.mags` (précondition).
+        let res = self.mags.try_dealloc(NonNull::new_unchecked(mag.cast()), provider);
+        debug_assert!(res.is_ok(), "magazine: {res:?}");
+    }
+}
+
+/// Nombre de rounds d'un magazine (0 si `null`).
+///
+/// # Safety
+/// `mag` est null ou un magazine valide détenu par l'appelant.
+unsafe fn rounds<const M: usize>(mag: *mut Magazine<M>) -> usize {
+    if mag.is_null() {
+        0
+    } else {
+        (*mag).rounds
+    }
+}
+
+/// Dépile un round (`None` si `mag` est null ou vide).
+///
+/// # Safety
+/// Comme `rounds`.
+unsafe fn pop<const M: usize>(mag: *mut Magazine<M>) -> Option<NonNull<u8>> {
+    if rounds(mag) == 0 {
+        return None;
+    }
+    (*mag).rounds -= 1;
+    NonNull::new((*mag).objs[(*mag).rounds])
+}
+
+/// Empile `obj` si le magazine a moins de `size` rounds ; `false` sinon
+/// (ou si `mag` est null).
+///
+/// # Safety
+/// Comme `rounds`.
+unsafe fn push<const M: usize>(mag: *mut Magazine<M>, obj: NonNull<u8>, size: usize) -> bool {
+    if mag.is_null() || (*mag).rounds >= size.min(M) {
+        return false;
+    }
+    (*mag).objs[(*mag).rounds] = obj.as_ptr();
+    (*mag).rounds += 1;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(miri)]
+    type Prov = crate::page_provider::TestPageProvider;
+    #[cfg(not(miri))]
+    type Prov = crate::page_provider::StaticPageProvider<16>;
+
+    fn magazines() -> MagazineCache<Prov, 2, 4> {
+        MagazineCache::new(Cache::new(64, 8), Prov::new())
+    }
+
+    #[test]
+    fn warm_magazines_bypass_the_cache() {
+        let m = magazines();
+
+        let objs: [*mut u8; 4] = core::array::from_fn(|_| m.alloc_on(0));
+        assert!(objs.iter().all(|p| !p.is_null()));
+        for &p in objs.iter() {
+            unsafe { m.free_on(0, p) };
+        }
+        // Les 4 objets sont dans le magazine de CPU 0, pas rendus au cache.
+        assert_eq!(m.live_objects(), 0);
+        assert_eq!(m.lock_cache().live_objects(), 4);
+
+        // Réalloués depuis le magazine (LIFO), sans toucher au cache.
+        let again: [*mut u8; 4] = core::array::from_fn(|_| m.alloc_on(0));
+        assert_eq!(again, [objs[3], objs[2], objs[1], objs[0]]);
+        assert_eq!(m.lock_cache().live_objects(), 4);
+
+        for &p in again.iter() {
+            unsafe { m.free_on(0, p) };
+        }
+        assert!(m.shrink() > 0);
+        assert_eq!(m.lock_cache().live_objects(), 0);
+    }
+
+    #[test]
+    fn full_magazines_travel_through_the_depot() {
+        let m = magazines();
+
+        // CPU 0 libère 3 magazines de rounds : loaded + previous pleins, le
+        // troisième pousse un magazine plein vers le dépôt.
+        let objs: [*mut u8; 12] = core::array::from_fn(|_| m.alloc_on(0));
+        for &p in objs.iter() {
+            unsafe { m.free_on(0, p) };
+        }
+        assert_eq!(m.depot_full_magazines(), 1);
+
+        // CPU 1 n'a rien : il prend le magazine plein du dépôt.
+        let p = m.alloc_on(1);
+        assert!(objs.contains(&p));
+        assert_eq!(m.depot_full_magazines(), 0);
+        unsafe { m.free_on(1, p) };
+
+        assert_eq!(m.live_objects(), 0);
+        m.shrink();
+        assert_eq!(m.lock_cache().live_objects(), 0);
+    }
+
+    #[test]
+    fn magazine_size_and_depot_limit_are_tunable() {
+        let m = magazines();
+        m.set_magazine_size(2);
+        m.set_depot_limit(0);
+        assert_eq!(m.magazine_size(), 2);
+
+        let objs: [*mut u8; 8] = core::array::from_fn(|_| m.alloc_on(0));
+        for &p in objs.iter() {
+            unsafe { m.free_on(0, p) };
+        }
+        // 2 magazines de 2 rounds gardés par le CPU, dépôt fermé : le reste
+        // est rendu au cache.
+        assert_eq!(m.depot_full_magazines(), 0);
+        assert_eq!(m.lock_cache().live_objects(), 4);
+
+        m.set_magazine_size(usize::MAX);
+        assert_eq!(m.magazine_size(), 4);
+        m.shrink();
+    }
+}
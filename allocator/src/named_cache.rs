@@ -0,0 +1,257 @@
+//! Caches nommés créés à la demande (équivalent de `kmem_cache_create`).
+//!
+//! En plus des caches anonymes des size classes, l'utilisateur peut
+//! enregistrer auprès du `SlabAllocator` des caches dédiés à un type d'objet
+//! ("inode", "dentry", ...). Ils partagent le provider de l'allocateur.
+//!
+//! Pas de heap : les caches vivent dans un tableau de `MAX_NAMED_CACHES`
+//! emplacements. Un `CacheHandle` désigne un emplacement + une génération,
+//! ce qui rend inutilisable un handle vers un cache détruit.
+
+use core::ops::BitOr;
+use core::ptr::NonNull;
+
+use crate::cache::{Cache, ObjectFn};
+use crate::page_provider::PageProvider;
+use crate::slab::{FreelistRandom, Slab};
+
+/// Nombre maximal de caches nommés vivants simultanément.
+pub const MAX_NAMED_CACHES: usize = 16;
+
+/// Étiquette des slabs du cache nommé de l'emplacement `i` : `NAMED_TAG_BASE + i`
+/// (hors des index de size classes, distinct de `NO_TAG`).
+pub const NAMED_TAG_BASE: u16 = 0x8000;
+
+/// Taille d'une ligne de cache CPU (pour `CacheFlags::HWCACHE_ALIGN`).
+pub const CACHE_LINE_SIZE: usize = 64;
+
+/// Options de création d'un cache (équivalent des `SLAB_*` du kernel).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CacheFlags(u32);
+
+impl CacheFlags {
+    /// Aucune option.
+    pub const NONE: Self = Self(0);
+    /// Aligne les objets sur une ligne de cache (`SLAB_HWCACHE_ALIGN`).
+    pub const HWCACHE_ALIGN: Self = Self(1 << 0);
+    /// Freelist initiale de chaque nouveau slab mélangée
+    /// (`CONFIG_SLAB_FREELIST_RANDOM`, voir `Cache::with_random_freelist`) ;
+    /// le générateur vient de `SlabAllocator::with_random_freelist`.
+    pub const RANDOM_FREELIST: Self = Self(1 << 1);
+
+    /// Vrai si toutes les options de `other` sont présentes.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for CacheFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Erreurs de création / destruction de caches nommés.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CacheError {
+    /// Taille/alignement impossibles dans une page.
+    InvalidLayout,
+    /// Tous les emplacements sont occupés.
+    NoFreeSlot,
+    /// Un cache vivant porte déjà ce nom.
+    DuplicateName,
+    /// Le handle désigne un cache détruit (ou n'a jamais été valide).
+    StaleHandle,
+    /// Le cache contient encore `live` objets alloués.
+    Busy { live: usize },
+    /// `CacheFlags::RANDOM_FREELIST` demandé sans générateur configuré.
+    NoRandomSource,
+}
+
+/// Référence vers un cache nommé.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CacheHandle {
+    slot: u16,
+    generation: u32,
+}
+
+struct NamedCache {
+    name: &'static str,
+    generation: u32,
+    cache: Cache,
+}
+
+/// Registre des caches nommés d'un allocateur.
+pub struct NamedCaches {
+    slots: [Option<NamedCache>; MAX_NAMED_CACHES],
+    /// Génération courante de chaque emplacement (incrémentée à la destruction).
+    generations: [u32; MAX_NAMED_CACHES],
+    /// Générateur des caches créés avec `CacheFlags::RANDOM_FREELIST`.
+    random: Option<FreelistRandom>,
+}
+
+impl NamedCaches {
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { None }; MAX_NAMED_CACHES],
+            generations: [0; MAX_NAMED_CACHES],
+            random: None,
+        }
+    }
+
+    /// Générateur dont chaque cache créé avec `CacheFlags::RANDOM_FREELIST`
+    /// tire le sien.
+    pub const fn set_random_freelist(&mut self, random: FreelistRandom) {
+        self.random = Some(random);
+    }
+
+    /// Enregistre un nouveau cache (constructeur / destructeur optionnels).
+    pub fn create(
+        &mut self,
+        name: &'static str,
+        size: usize,
+        align: usize,
+        flags: CacheFlags,
+        ctor: Option<ObjectFn>,
+        dtor: Option<ObjectFn>,
+    ) -> Result<CacheHandle, CacheError> {
+        let mut align = align;
+        if flags.contains(CacheFlags::HWCACHE_ALIGN) && align < CACHE_LINE_SIZE {
+            align = CACHE_LINE_SIZE;
+        }
+
+        let mut cache = Cache::new(size, align).with_ctor(ctor, dtor);
+        if !Slab::fits(cache.stride(), cache.align()) {
+            return Err(CacheError::InvalidLayout);
+        }
+        if flags.contains(CacheFlags::RANDOM_FREELIST) {
+            let random = self.random.as_mut().ok_or(CacheError::NoRandomSource)?;
+            cache = cache.with_random_freelist(random.fork());
+        }
+
+        if self.find(name).is_some() {
+            return Err(CacheError::DuplicateName);
+        }
+
+        let slot = self
+            .slots
+            .iter()
+            .position(Option::is_none)
+            .ok_or(CacheError::NoFreeSlot)?;
+
+        let generation = self.generations[slot];
+        self.slots[slot] = Some(NamedCache {
+            name,
+            generation,
+            cache: cache.with_tag(NAMED_TAG_BASE + slot as u16),
+        });
+
+        Ok(CacheHandle {
+            slot: slot as u16,
+            generation,
+        })
+    }
+
+    /// Détruit un cache vide et rend ses pages au provider.
+    pub fn destroy<P: PageProvider>(
+        &mut self,
+        handle: CacheHandle,
+        provider: &mut P,
+    ) -> Result<(), CacheError> {
+        let cache = self.get_mut(handle).ok_or(CacheError::StaleHandle)?;
+
+        let live = cache.live_objects();
+        if live > 0 {
+            return Err(CacheError::Busy { live });
+        }
+
+        // SAFETY: aucun objet vivant ; les pages viennent de `provider` (partagé par l'allocateur).
+        unsafe { cache.release_all(provider) };
+
+        let slot = handle.slot as usize;
+        self.slots[slot] = None;
+        self.generations[slot] = self.generations[slot].wrapping_add(1);
+        Ok(())
+    }
+
+    /// Cache vivant désigné par `handle`.
+    pub fn get(&self, handle: CacheHandle) -> Option<&Cache> {
+        match self.slots.get(handle.slot as usize)? {
+            Some(named) if named.generation == handle.generation => Some(&named.cache),
+            _ => None,
+        }
+    }
+
+    /// Cache vivant désigné par `handle` (mutable).
+    pub fn get_mut(&mut self, handle: CacheHandle) -> Option<&mut Cache> {
+        match self.slots.get_mut(handle.slot as usize)? {
+            Some(named) if named.generation == handle.generation => Some(&mut named.cache),
+            _ => None,
+        }
+    }
+
+    /// Nom du cache désigné par `handle`.
+    pub fn name(&self, handle: CacheHandle) -> Option<&'static str> {
+        match self.slots.get(handle.slot as usize)? {
+            Some(named) if named.generation == handle.generation => Some(named.name),
+            _ => None,
+        }
+    }
+
+    /// Recherche un cache vivant par son nom.
+    pub fn find(&self, name: &str) -> Option<CacheHandle> {
+        self.slots.iter().enumerate().find_map(|(slot, named)| match named {
+            Some(n) if n.name == name => Some(CacheHandle {
+                slot: slot as u16,
+                generation: n.generation,
+            }),
+            _ => None,
+        })
+    }
+
+    /// Vrai si `ptr` pointe dans une page d'un des caches nommés.
+    pub fn owns(&self, ptr: NonNull<u8>) -> bool {
+        self.slots.iter().flatten().any(|named| named.cache.owns(ptr))
+    }
+
+    /// Alloue un objet dans le cache `handle`.
+    pub fn alloc<P: PageProvider>(&mut self, handle: CacheHandle, provider: &mut P) -> Option<NonNull<u8>> {
+        self.get_mut(handle)?.alloc(provider)
+    }
+
+    /// Rend TOUTES les pages de tous les caches (démontage de l'allocateur).
+    ///
+    /// Appelle `report(nom, objets vivants)` pour chaque cache.
+    ///
+    /// # Safety
+    /// - `provider` doit être celui qui a fourni les pages.
+    /// - aucun objet de ces caches ne doit être utilisé après l'appel.
+    pub unsafe fn release_all<P: PageProvider>(
+        &mut self,
+        provider: &mut P,
+        mut report: impl FnMut(&'static str, usize),
+    ) {
+        for named in self.slots.iter_mut().flatten() {
+            // SAFETY: précondition de la fonction.
+            let live = unsafe { named.cache.release_all(provider) };
+            report(named.name, live);
+        }
+    }
+
+    /// Rend au provider les slabs vides de tous les caches nommés.
+    pub fn shrink<P: PageProvider>(&mut self, provider: &mut P) -> usize {
+        self.slots
+            .iter_mut()
+            .flatten()
+            .map(|named| named.cache.shrink(provider))
+            .sum()
+    }
+}
+
+impl Default for NamedCaches {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,284 @@
+//! Cache typé : `ObjectCache<T>` et son pointeur possédant `SlabBox<T>`.
+//!
+//! `Cache` manipule des `NonNull<u8>` et laisse l'appelant se souvenir de la
+//! taille de ses objets. `ObjectCache<T, P>` dérive taille et alignement de `T`
+//! et rend un `SlabBox<T>` qui libère l'objet (après l'avoir droppé) quand il
+//! sort de portée : le cas courant ne demande aucun `unsafe`.
+//!
+//! ```ignore
+//! let inodes: ObjectCache<Inode, _> = ObjectCache::new(StaticPageProvider::<16>::new())?;
+//! let mut ino = inodes.alloc(Inode::default()).expect("oom");
+//! ino.nlink += 1;
+//! // drop(ino) => Inode::drop puis retour au cache
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+
+use crate::cache::Cache;
+use crate::named_cache::CacheError;
+use crate::page_provider::PageProvider;
+use crate::slab::{FreelistRandom, Slab};
+use crate::sync::SpinLock;
+
+/// Cache d'objets de type `T`, propriétaire de son provider.
+///
+/// Les allocations prennent `&self` (spinlock interne) : plusieurs `SlabBox`
+/// peuvent vivre en même temps, chacun empruntant le cache.
+pub struct ObjectCache<T, P: PageProvider> {
+    inner: SpinLock<Inner<P>>,
+    _marker: PhantomData<T>,
+}
+
+struct Inner<P> {
+    cache: Cache,
+    provider: P,
+}
+
+// SAFETY: les pages des slabs appartiennent exclusivement à ce cache (obtenues
+// via `provider`) ; déplacer le cache déplace les `T` qu'il pourrait encore
+// contenir, d'où `T: Send`.
+unsafe impl<T: Send, P: PageProvider + Send> Send for ObjectCache<T, P> {}
+
+// SAFETY: tout accès à `Inner` passe par le spinlock. Partager le cache permet
+// de créer / libérer des `T` depuis plusieurs threads, d'où `T: Send`.
+unsafe impl<T: Send, P: PageProvider + Send> Sync for ObjectCache<T, P> {}
+
+impl<T, P: PageProvider> ObjectCache<T, P> {
+    /// Crée un cache pour `T` au-dessus de `provider`.
+    ///
+    /// `CacheError::InvalidLayout` si un `T` ne tient pas dans une page.
+    pub fn new(provider: P) -> Result<Self, CacheError> {
+        let cache = Cache::new(mem::size_of::<T>(), mem::align_of::<T>());
+        if !Slab::fits(cache.stride(), cache.align()) {
+            return Err(CacheError::InvalidLayout);
+        }
+
+        Ok(Self {
+            inner: SpinLock::new(Inner { cache, provider }),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Mélange la freelist initiale de chaque nouveau slab (voir
+    /// `Cache::with_random_freelist`) ; la graine doit être imprévisible
+    /// (voir `FreelistRandom::with_seed`).
+    pub fn with_random_freelist(mut self, random: FreelistRandom) -> Self {
+        let inner = self.inner.get_mut();
+        let cache = mem::replace(&mut inner.cache, Cache::new(0, 1));
+        inner.cache = cache.with_random_freelist(random);
+        self
+    }
+
+    /// Place `value` dans un objet du cache.
+    ///
+    /// En cas d'OOM, `value` est rendue dans `Err`.
+    pub fn try_alloc(&self, value: T) -> Result<SlabBox<'_, T, P>, T> {
+        let slot = {
+            let mut inner = self.inner.lock();
+            let Inner { cache, provider } = &mut *inner;
+            cache.alloc(provider)
+        };
+        let Some(slot) = slot else { return Err(value) };
+
+        let ptr = slot.cast::<T>();
+        // SAFETY: objet fraîchement alloué, de taille/alignement >= ceux de `T`.
+        unsafe { ptr.as_ptr().write(value) };
+
+        Ok(SlabBox { ptr, cache: self })
+    }
+
+    /// Comme `try_alloc`, mais `None` en cas d'OOM (`value` est droppée).
+    pub fn alloc(&self, value: T) -> Option<SlabBox<'_, T, P>> {
+        self.try_alloc(value).ok()
+    }
+
+    /// Nombre d'objets actuellement alloués.
+    pub fn live_objects(&self) -> usize {
+        self.inner.lock().cache.live_objects()
+    }
+
+    /// Rend au provider les slabs vides ; retourne le nombre de pages rendues.
+    pub fn shrink(&self) -> usize {
+        let mut inner = self.inner.lock();
+        let Inner { cache, provider } = &mut *inner;
+        cache.shrink(provider)
+    }
+
+    /// Règle le nombre de slabs vides gardés en réserve.
+    pub fn set_empty_limit(&self, limit: usize) {
+        self.inner.lock().cache.set_empty_limit(limit);
+    }
+
+    /// Accès direct au provider (nécessite l'exclusivité, donc aucun `SlabBox` vivant).
+    pub fn provider_mut(&mut self) -> &mut P {
+        &mut self.inner.get_mut().provider
+    }
+
+    /// Rend l'objet `ptr` au cache sans le dropper.
+    ///
+    /// # Safety
+    /// `ptr` vient de `try_alloc` sur ce cache et n'est plus utilisé.
+    unsafe fn free(&self, ptr: NonNull<T>) {
+        let mut inner = self.inner.lock();
+        let Inner { cache, provider } = &mut *inner;
+        // SAFETY: précondition ; les pages du cache viennent de `provider`.
+        unsafe { cache.dealloc(ptr.cast(), provider) };
+    }
+}
+
+/// Démontage : tous les `SlabBox` empruntent le cache, ils sont donc déjà
+/// détruits ; toutes les pages sont rendues au provider.
+impl<T, P: PageProvider> Drop for ObjectCache<T, P> {
+    fn drop(&mut self) {
+        let Inner { cache, provider } = self.inner.get_mut();
+        // SAFETY: aucun objet vivant (emprunts terminés, sauf `SlabBox::leak`) ;
+        // pages issues de `provider`.
+        unsafe { cache.release_all(provider) };
+    }
+}
+
+/// Pointeur possédant vers un `T` alloué dans un `ObjectCache`.
+///
+/// Au drop, `T` est droppé puis l'objet est rendu au cache.
+pub struct SlabBox<'a, T, P: PageProvider> {
+    ptr: NonNull<T>,
+    cache: &'a ObjectCache<T, P>,
+}
+
+// SAFETY: `SlabBox` possède son `T` comme un `Box` ; le rendre depuis un autre
+// thread passe par le spinlock du cache (`ObjectCache: Sync`).
+unsafe impl<T: Send, P: PageProvider + Send> Send for SlabBox<'_, T, P> {}
+// SAFETY: `&SlabBox` ne donne que `&T`.
+unsafe impl<T: Sync, P: PageProvider + Send> Sync for SlabBox<'_, T, P> {}
+
+impl<'a, T, P: PageProvider> SlabBox<'a, T, P> {
+    /// Sort la valeur du cache et libère l'objet.
+    pub fn into_inner(this: Self) -> T {
+        let this = mem::ManuallyDrop::new(this);
+        // SAFETY: `ptr` contient un `T` valide, lu une seule fois (pas de drop ensuite).
+        let value = unsafe { this.ptr.as_ptr().read() };
+        // SAFETY: objet de ce cache, plus utilisé.
+        unsafe { this.cache.free(this.ptr) };
+        value
+    }
+
+    /// Abandonne la propriété : l'objet ne sera jamais rendu au cache
+    /// (ses pages le seront quand même au drop de l'`ObjectCache`).
+    pub fn leak(this: Self) -> &'a mut T {
+        let this = mem::ManuallyDrop::new(this);
+        // SAFETY: `T` valide, plus aucun autre accès via `SlabBox`.
+        unsafe { &mut *this.ptr.as_ptr() }
+    }
+
+    /// Pointeur brut vers la valeur.
+    pub fn as_ptr(this: &Self) -> *const T {
+        this.ptr.as_ptr()
+    }
+}
+
+impl<T, P: PageProvider> Deref for SlabBox<'_, T, P> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: `ptr` contient un `T` valide possédé par ce `SlabBox`.
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T, P: PageProvider> DerefMut for SlabBox<'_, T, P> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: idem, accès exclusif via `&mut self`.
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T, P: PageProvider> Drop for SlabBox<'_, T, P> {
+    fn drop(&mut self) {
+        // SAFETY: `T` valide, droppé une seule fois, puis l'objet est rendu.
+        unsafe {
+            self.ptr.as_ptr().drop_in_place();
+            self.cache.free(self.ptr);
+        }
+    }
+}
+
+impl<T: fmt::Debug, P: PageProvider> fmt::Debug for SlabBox<'_, T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(miri)]
+    type Prov = crate::page_provider::TestPageProvider;
+    #[cfg(not(miri))]
+    type Prov = crate::page_provider::StaticPageProvider<8>;
+
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        x: u64,
+        y: u64,
+        z: u32,
+    }
+
+    #[test]
+    fn boxes_free_on_drop() {
+        let cache: ObjectCache<Point, Prov> = ObjectCache::new(Prov::new()).unwrap();
+
+        let mut a = cache.alloc(Point { x: 1, y: 2, z: 3 }).expect("a");
+        let b = cache.alloc(Point { x: 4, y: 5, z: 6 }).expect("b");
+        a.x += 10;
+        assert_eq!(*a, Point { x: 11, y: 2, z: 3 });
+        assert_eq!(SlabBox::as_ptr(&a) as usize % mem::align_of::<Point>(), 0);
+        assert_eq!(cache.live_objects(), 2);
+
+        drop(a);
+        assert_eq!(cache.live_objects(), 1);
+        assert_eq!(SlabBox::into_inner(b), Point { x: 4, y: 5, z: 6 });
+        assert_eq!(cache.live_objects(), 0);
+    }
+
+    #[test]
+    fn drop_runs_value_destructor() {
+        use std::rc::Rc;
+
+        let cache: ObjectCache<Rc<()>, Prov> = ObjectCache::new(Prov::new()).unwrap();
+        let rc = Rc::new(());
+        let boxed = cache.alloc(rc.clone()).expect("alloc");
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(boxed);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn random_freelist_shuffles_objects() {
+        use crate::slab::FreelistRandom;
+
+        let cache: ObjectCache<[u64; 8], Prov> = ObjectCache::new(Prov::new())
+            .unwrap()
+            .with_random_freelist(FreelistRandom::with_seed(0x5EED));
+
+        // Dans l'ordre des adresses, chaque objet suivrait le précédent.
+        let boxes: [_; 16] = core::array::from_fn(|_| cache.alloc([0; 8]).expect("alloc"));
+        let ascending = boxes.windows(2).all(|w| {
+            let (a, b) = (&*w[0] as *const _ as usize, &*w[1] as *const _ as usize);
+            b == a + 64
+        });
+        assert!(!ascending, "ordre non mélangé");
+    }
+
+    #[test]
+    fn oversized_type_is_rejected() {
+        assert_eq!(
+            ObjectCache::<[u8; 4096], Prov>::new(Prov::new()).err(),
+            Some(CacheError::InvalidLayout)
+        );
+    }
+}
@@ -5,6 +5,12 @@ use core::cell::UnsafeCell;
 pub const PAGE_SIZE: usize = 4096;
 /// L'allocateur slab repose sur ce provider (slow path).
 pub trait PageProvider {
+    /// Vrai si chaque page (ou suite de pages) renvoyée est remplie de zéros.
+    ///
+    /// Permet à `alloc_zeroed` de ne pas effacer une seconde fois les objets
+    /// jamais distribués depuis l'obtention de leur page.
+    const ZEROES_PAGES: bool = false;
+
     /// Alloue une page de taille PAGE_SIZE (4096) et renvoie un pointeur non nul.
     ///
     /// Retourne `None` en cas d'OOM.
@@ -16,6 +22,64 @@ pub trait PageProvider {
     /// - `ptr` doit provenir d'un `alloc_page` de CE provider.
     /// - `ptr` ne doit pas être déjà libéré.
     fn dealloc_page(&mut self, ptr: NonNull<u8>);
+
+    /// Alloue `count` pages CONTIGUËS (alignées sur PAGE_SIZE).
+    ///
+    /// Utilisé par le chemin "grosses allocations". L'implémentation par
+    /// défaut ne sait fournir qu'une page à la fois.
+    fn alloc_pages(&mut self, count: usize) -> Option<NonNull<u8>> {
+        if count == 1 {
+            self.alloc_page()
+        } else {
+            None
+        }
+    }
+
+    /// Libère une suite de `count` pages obtenue par `alloc_pages(count)`.
+    ///
+    /// # Safety
+    /// - `ptr`/`count` doivent provenir d'un `alloc_pages` de CE provider.
+    /// - le bloc ne doit pas être déjà libéré.
+    fn dealloc_pages(&mut self, ptr: NonNull<u8>, count: usize) {
+        debug_assert_eq!(count, 1, "dealloc_pages: provider sans support multi-pages");
+        self.dealloc_page(ptr);
+    }
+
+    /// La page commençant à `page` a-t-elle été fournie par ce provider (et
+    /// pas encore rendue) ?
+    ///
+    /// `None` si le provider ne le sait pas : l'allocateur parcourt alors ses
+    /// propres slabs. Ne doit jamais lire la mémoire de `page`.
+    fn owns_page(&self, page: NonNull<u8>) -> Option<bool> {
+        let _ = page;
+        None
+    }
+}
+
+/// Un provider emprunté reste un provider : permet de garder la main sur le
+/// backend (et de l'inspecter) après la destruction de l'allocateur.
+impl<P: PageProvider + ?Sized> PageProvider for &mut P {
+    const ZEROES_PAGES: bool = P::ZEROES_PAGES;
+
+    fn alloc_page(&mut self) -> Option<NonNull<u8>> {
+        (**self).alloc_page()
+    }
+
+    fn dealloc_page(&mut self, ptr: NonNull<u8>) {
+        (**self).dealloc_page(ptr)
+    }
+
+    fn alloc_pages(&mut self, count: usize) -> Option<NonNull<u8>> {
+        (**self).alloc_pages(count)
+    }
+
+    fn dealloc_pages(&mut self, ptr: NonNull<u8>, count: usize) {
+        (**self).dealloc_pages(ptr, count)
+    }
+
+    fn owns_page(&self, page: NonNull<u8>) -> Option<bool> {
+        (**self).owns_page(page)
+    }
 }
 
 /// Une page de 4096 bytes alignée sur 4096.
@@ -28,29 +92,39 @@ struct Page([u8; PAGE_SIZE]);
 ///
 /// - Allocation: pop sur une stack d'indices.
 /// - Free: push sur la stack.
+/// - Multi-pages: recherche linéaire d'une suite de pages libres (`used`).
 /// - OOM: None.
 pub struct StaticPageProvider<const N: usize> {
     pool: [UnsafeCell<Page>; N],
     free_stack: [usize; N],
     free_len: usize,
+    used: [bool; N],
 }
 
 impl<const N: usize> StaticPageProvider<N> {
     /// Crée un provider avec N pages disponibles.
-    pub fn new() -> Self {
-	    let mut free_stack = [0usize; N];
-	    let mut i = 0;
-	    while i < N {
-		free_stack[i] = i;
-		i += 1;
-	    }
+    ///
+    /// `const` : le pool peut vivre dans un `static` (ex. `#[global_allocator]`).
+    pub const fn new() -> Self {
+        let mut free_stack = [0usize; N];
+        let mut i = 0;
+        while i < N {
+            free_stack[i] = i;
+            i += 1;
+        }
 
-	    Self {
-		pool: core::array::from_fn(|_| UnsafeCell::new(Page([0u8; PAGE_SIZE]))),
-		free_stack,
-		free_len: N,
-	    }
-	}
+        Self {
+            pool: [const { UnsafeCell::new(Page([0u8; PAGE_SIZE])) }; N],
+            free_stack,
+            free_len: N,
+            used: [false; N],
+        }
+    }
+
+    /// Nombre de pages encore disponibles dans le pool.
+    pub fn free_pages(&self) -> usize {
+        self.free_len
+    }
 
     fn page_ptr(&self, idx: usize) -> NonNull<u8> {
     // On évite `self.pool[idx]` (indexing => création d'une référence => retag Miri).
@@ -73,7 +147,7 @@ impl<const N: usize> StaticPageProvider<N> {
 	    }
 
 	    let off = p - base;
-	    if off % page_size != 0 {
+	    if !off.is_multiple_of(page_size) {
 		return None;
 	    }
 
@@ -82,6 +156,9 @@ impl<const N: usize> StaticPageProvider<N> {
 }
 
 impl<const N: usize> PageProvider for StaticPageProvider<N> {
+    /// Les pages sont effacées à chaque `alloc_page` / `alloc_pages`.
+    const ZEROES_PAGES: bool = true;
+
     fn alloc_page(&mut self) -> Option<NonNull<u8>> {
         if self.free_len == 0 {
             return None;
@@ -89,6 +166,7 @@ impl<const N: usize> PageProvider for StaticPageProvider<N> {
 
         self.free_len -= 1;
         let idx = self.free_stack[self.free_len];
+        self.used[idx] = true;
         let page = self.page_ptr(idx);
 
         unsafe {
@@ -107,14 +185,96 @@ impl<const N: usize> PageProvider for StaticPageProvider<N> {
             return;
         };
 
-        if self.free_len >= N {
+        if self.free_len >= N || !self.used[idx] {
             debug_assert!(false, "dealloc_page: free stack overflow (double free?)");
             return;
         }
 
+        self.used[idx] = false;
         self.free_stack[self.free_len] = idx;
         self.free_len += 1;
     }
+
+    fn alloc_pages(&mut self, count: usize) -> Option<NonNull<u8>> {
+        if count == 1 {
+            return self.alloc_page();
+        }
+        if count == 0 || count > self.free_len {
+            return None;
+        }
+
+        // Première suite de `count` pages libres consécutives.
+        let mut start = 0;
+        let mut run = 0;
+        let mut i = 0;
+        while i < N && run < count {
+            if self.used[i] {
+                run = 0;
+                start = i + 1;
+            } else {
+                run += 1;
+            }
+            i += 1;
+        }
+        if run < count {
+            return None;
+        }
+
+        for idx in start..start + count {
+            self.used[idx] = true;
+        }
+
+        // Retirer ces indices de la stack (compaction).
+        let mut kept = 0;
+        for j in 0..self.free_len {
+            let idx = self.free_stack[j];
+            if !self.used[idx] {
+                self.free_stack[kept] = idx;
+                kept += 1;
+            }
+        }
+        self.free_len = kept;
+
+        let first = self.page_ptr(start);
+        unsafe {
+            // # Safety
+            // - les pages start..start+count sont contiguës dans `pool` (tableau).
+            // - elles viennent d'être marquées `used` => exclusives à cet appel.
+            core::ptr::write_bytes(first.as_ptr(), 0, count * PAGE_SIZE);
+        }
+
+        Some(first)
+    }
+
+    fn dealloc_pages(&mut self, ptr: NonNull<u8>, count: usize) {
+        let Some(first) = self.index_from_ptr(ptr) else {
+            debug_assert!(false, "dealloc_pages: ptr not from this pool or misaligned");
+            return;
+        };
+        if first + count > N {
+            debug_assert!(false, "dealloc_pages: run out of pool bounds");
+            return;
+        }
+
+        for idx in first..first + count {
+            // SAFETY: idx < N vérifié ci-dessus.
+            let page = self.page_ptr(idx);
+            self.dealloc_page(page);
+        }
+    }
+
+    fn owns_page(&self, page: NonNull<u8>) -> Option<bool> {
+        if N == 0 {
+            return Some(false);
+        }
+        Some(self.index_from_ptr(page).is_some_and(|idx| self.used[idx]))
+    }
+}
+
+impl<const N: usize> Default for StaticPageProvider<N> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
@@ -137,6 +297,25 @@ mod static_provider_tests {
         let c = p.alloc_page().expect("page c");
         assert_eq!((c.as_ptr() as usize) % PAGE_SIZE, 0);
     }
+
+    #[test]
+    fn static_provider_contiguous_runs() {
+        let mut p = StaticPageProvider::<4>::new();
+
+        let single = p.alloc_page().expect("single");
+        let run = p.alloc_pages(3).expect("run of 3");
+        assert_eq!((run.as_ptr() as usize) % PAGE_SIZE, 0);
+        assert!(p.alloc_page().is_none());
+
+        // Les 3 pages sont utilisables d'un bloc.
+        unsafe { core::ptr::write_bytes(run.as_ptr(), 0xAB, 3 * PAGE_SIZE) };
+
+        p.dealloc_pages(run, 3);
+        assert!(p.alloc_pages(4).is_none(), "la page `single` casse la contiguïté");
+        p.dealloc_page(single);
+        let all = p.alloc_pages(4).expect("whole pool");
+        p.dealloc_pages(all, 4);
+    }
 }
 
 #[cfg(any(test, feature = "test-provider"))]
@@ -147,7 +326,8 @@ pub mod test_provider {
     use std::vec::Vec;
 
     pub struct TestPageProvider {
-        pages: Vec<NonNull<u8>>,
+        /// Blocs vivants : (début, nombre de pages).
+        pages: Vec<(NonNull<u8>, usize)>,
     }
 
     impl TestPageProvider {
@@ -156,37 +336,64 @@ pub mod test_provider {
         }
     }
 
+    impl Default for TestPageProvider {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     impl PageProvider for TestPageProvider {
         fn alloc_page(&mut self) -> Option<NonNull<u8>> {
-            let layout = Layout::from_size_align(PAGE_SIZE, PAGE_SIZE).ok()?;
+            self.alloc_pages(1)
+        }
 
-            // SAFETY: layout valide, alloc renvoie un ptr aligné layout.align()
+        fn dealloc_page(&mut self, ptr: NonNull<u8>) {
+            self.dealloc_pages(ptr, 1)
+        }
+
+        fn alloc_pages(&mut self, count: usize) -> Option<NonNull<u8>> {
+            let size = count.checked_mul(PAGE_SIZE)?;
+            if size == 0 {
+                return None;
+            }
+            let layout = Layout::from_size_align(size, PAGE_SIZE).ok()?;
+
+            // SAFETY: layout valide (taille non nulle), alloc renvoie un ptr aligné layout.align()
             let ptr = unsafe { alloc(layout) };
             let nn = NonNull::new(ptr)?;
 
-            self.pages.push(nn);
+            self.pages.push((nn, count));
             Some(nn)
         }
 
-        fn dealloc_page(&mut self, ptr: NonNull<u8>) {
-            let layout = Layout::from_size_align(PAGE_SIZE, PAGE_SIZE)
-                .expect("layout must be valid");
-
-            let idx = self.pages.iter().position(|&p| p == ptr)
+        fn dealloc_pages(&mut self, ptr: NonNull<u8>, count: usize) {
+            let idx = self.pages.iter().position(|&(p, _)| p == ptr)
                 .expect("double free / unknown page");
-            self.pages.swap_remove(idx);
+            let (_, n) = self.pages.swap_remove(idx);
+            assert_eq!(n, count, "dealloc_pages: page count mismatch");
+
+            let layout = Layout::from_size_align(n * PAGE_SIZE, PAGE_SIZE)
+                .expect("layout must be valid");
 
             // SAFETY:
-            // - ptr provient de alloc_page() avec le même Layout
+            // - ptr provient de alloc_pages(n) avec le même Layout
             // - ptr n'a pas déjà été libéré (on le retire de pages)
             unsafe { dealloc(ptr.as_ptr(), layout) };
         }
+
+        fn owns_page(&self, page: NonNull<u8>) -> Option<bool> {
+            let addr = page.as_ptr() as usize;
+            Some(self.pages.iter().any(|&(p, n)| {
+                let start = p.as_ptr() as usize;
+                addr >= start && addr < start + n * PAGE_SIZE
+            }))
+        }
     }
     impl Drop for TestPageProvider {
 	    fn drop(&mut self) {
-		while let Some(p) = self.pages.pop() {
+		while let Some((p, n)) = self.pages.pop() {
 		    unsafe {
-		        std::alloc::dealloc(p.as_ptr(), std::alloc::Layout::from_size_align_unchecked(PAGE_SIZE, PAGE_SIZE));
+		        std::alloc::dealloc(p.as_ptr(), std::alloc::Layout::from_size_align_unchecked(n * PAGE_SIZE, PAGE_SIZE));
 		    }
 		}
 	    }
@@ -0,0 +1,410 @@
+//! Caches avant par CPU / par thread (équivalent du `cpu_slab` de SLUB).
+//!
+//! Chaque CPU (ou thread) possède, pour chaque size class, un slab "actif"
+//! gelé (voir `Cache::freeze_slab`) : il en tire ses objets et y rend ceux
+//! qu'il libère sans prendre le verrou du `Cache` partagé.
+//!
+//! Un objet libéré depuis un autre CPU que celui qui détient son slab est
+//! empilé sans verrou dans la pile `remote` du slab (`Slab::free_remote`) ;
+//! le propriétaire la récupère en lot quand son slab actif est épuisé.
+//!
+//! Avec `with_lock_free_freelist`, la freelist du slab est elle-même sans
+//! verrou (`AtomicFreeList`) : l'objet distant y est rendu directement, le
+//! propriétaire le reprend au prochain `alloc` sans attendre l'épuisement.
+//!
+//! Le cache n'est verrouillé que pour :
+//! - la recharge, quand le slab actif est épuisé et que sa pile `remote` est
+//!   vide (on échange le slab contre un partiel) ;
+//! - la libération d'un objet d'un slab non gelé (chemin normal du cache) ;
+//! - le `flush`, qui rend les slabs actifs au cache.
+//!
+//! L'identifiant de CPU vient de `cpu_id` : avec la feature `std`, un index
+//! attribué à chaque thread ; sans `std`, toujours 0 tant que l'appelant n'a
+//! pas fourni sa fonction (`with_cpu_id`) ou n'utilise pas `alloc_on` /
+//! `dealloc_on`. Deux threads qui tombent sur le même index partagent
+//! simplement le même slab actif (le verrou avant les sérialise).
+//!
+//! Ordre de verrouillage : avant, puis cache, puis provider.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::ptr::NonNull;
+
+use crate::allocator::{route, Route};
+use crate::concurrent::ConcurrentSlabAllocator;
+use crate::error::AllocError;
+use crate::page_provider::{PageProvider, PAGE_SIZE};
+use crate::size_class::{SizeClasses, DEFAULT_SIZE_CLASSES, FINE_SIZE_CLASSES};
+use crate::slab::Slab;
+use crate::sync::Lock;
+
+/// Slab actif d'un CPU pour une size class.
+struct Front {
+    active: Option<Slab>,
+}
+
+/// Allocateur slab `Sync` avec un cache avant par CPU et par size class.
+///
+/// ```ignore
+/// static SLAB: PerCpuSlabAllocator<StaticPageProvider<1024>, 8> =
+///     PerCpuSlabAllocator::new(StaticPageProvider::new()).with_cpu_id(current_core);
+/// ```
+pub struct PerCpuSlabAllocator<P: PageProvider, const CPUS: usize, const N: usize = 9> {
+    shared: ConcurrentSlabAllocator<P, N>,
+    fronts: [[Lock<Front>; N]; CPUS],
+    cpu_id: fn() -> usize,
+}
+
+// SAFETY: un slab actif n'est utilisé que sous le verrou de son `Front` ; le
+// reste de l'état est celui de `ConcurrentSlabAllocator`, protégé par ses verrous.
+unsafe impl<P: PageProvider + Send, const CPUS: usize, const N: usize> Send
+    for PerCpuSlabAllocator<P, CPUS, N>
+{
+}
+// SAFETY: idem, tout accès partagé passe par un verrou, sauf `free_remote`
+// qui n'utilise que la pile atomique (ou la freelist sans verrou) du slab.
+unsafe impl<P: PageProvider + Send, const CPUS: usize, const N: usize> Sync
+    for PerCpuSlabAllocator<P, CPUS, N>
+{
+}
+
+impl<P: PageProvider, const CPUS: usize> PerCpuSlabAllocator<P, CPUS> {
+    /// Allocateur avec la table par défaut (8..2048).
+    pub const fn new(provider: P) -> Self {
+        Self::with_classes(provider, DEFAULT_SIZE_CLASSES)
+    }
+}
+
+impl<P: PageProvider, const CPUS: usize> PerCpuSlabAllocator<P, CPUS, 26> {
+    /// Allocateur avec la table fine (voir `SlabAllocator::new_fine`).
+    pub const fn new_fine(provider: P) -> Self {
+        Self::with_classes(provider, FINE_SIZE_CLASSES)
+    }
+}
+
+impl<P: PageProvider, const CPUS: usize, const N: usize> PerCpuSlabAllocator<P, CPUS, N> {
+    /// Allocateur avec une table de size classes personnalisée.
+    pub const fn with_classes(provider: P, classes: SizeClasses<N>) -> Self {
+        assert!(CPUS > 0, "PerCpuSlabAllocator: au moins un CPU");
+        Self {
+            shared: ConcurrentSlabAllocator::with_classes(provider, classes),
+            fronts: [const { [const { Lock::new(Front { active: None }) }; N] }; CPUS],
+            cpu_id: default_cpu_id,
+        }
+    }
+
+    /// Remplace la fonction qui donne le CPU courant (index réduit modulo `CPUS`).
+    pub const fn with_cpu_id(mut self, cpu_id: fn() -> usize) -> Self {
+        self.cpu_id = cpu_id;
+        self
+    }
+
+    /// Slabs à freelist sans verrou (voir `Cache::with_lock_free_freelist`) :
+    /// un objet libéré par un autre CPU rejoint directement la freelist du
+    /// slab actif, où son propriétaire le reprend pendant que d'autres CPU y
+    /// libèrent encore, sans passer par la pile `remote` ni `drain_remote`.
+    pub const fn with_lock_free_freelist(mut self) -> Self {
+        self.shared.set_lock_free_freelist();
+        self
+    }
+
+    /// Allocateur partagé sous-jacent (caches, grosses allocations, provider).
+    pub fn shared(&self) -> &ConcurrentSlabAllocator<P, N> {
+        &self.shared
+    }
+
+    fn current_cpu(&self) -> usize {
+        (self.cpu_id)()
+    }
+
+    /// Alloue un bloc pour `layout` sur le CPU courant ; null en cas d'échec.
+    pub fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.alloc_on(self.current_cpu(), layout)
+    }
+
+    /// Comme `alloc`, sur le CPU `cpu`.
+    pub fn alloc_on(&self, cpu: usize, layout: Layout) -> *mut u8 {
+        self.try_alloc_on(cpu, layout).map_or(core::ptr::null_mut(), NonNull::as_ptr)
+    }
+
+    /// Comme `alloc_on`, avec la raison de l'échec.
+    pub fn try_alloc_on(&self, cpu: usize, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+        match route(self.shared.classes(), layout)? {
+            Route::Class(idx) => self.alloc_obj(cpu, idx).map(|(ptr, _)| ptr),
+            Route::Large => self.shared.try_alloc(layout),
+        }
+    }
+
+    /// Comme `alloc`, mais le bloc est rempli de zéros (voir `SlabAllocator::alloc_zeroed`).
+    pub fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.alloc_zeroed_on(self.current_cpu(), layout)
+    }
+
+    /// Comme `alloc_zeroed`, sur le CPU `cpu`.
+    pub fn alloc_zeroed_on(&self, cpu: usize, layout: Layout) -> *mut u8 {
+        match route(self.shared.classes(), layout) {
+            Ok(Route::Class(idx)) => match self.alloc_obj(cpu, idx) {
+                Ok((ptr, fresh)) => {
+                    // Les caches de classe n'ont pas de constructeur : un objet
+                    // neuf d'une page déjà nulle n'a pas besoin d'être effacé.
+                    if !(fresh && P::ZEROES_PAGES) {
+                        // SAFETY: objet de la classe `idx`, qui contient `layout.size()` octets.
+                        unsafe { ptr.as_ptr().write_bytes(0, layout.size()) };
+                    }
+                    ptr.as_ptr()
+                }
+                Err(_) => core::ptr::null_mut(),
+            },
+            Ok(Route::Large) => self.shared.alloc_zeroed(layout),
+            Err(_) => core::ptr::null_mut(),
+        }
+    }
+
+    /// Objet de la classe `idx` pris dans le slab actif de `cpu` (recharge si besoin).
+    fn alloc_obj(&self, cpu: usize, idx: usize) -> Result<(NonNull<u8>, bool), AllocError> {
+        let mut front = self.fronts[cpu % CPUS][idx].lock();
+        if let Some(obj) = front.active.as_mut().and_then(Slab::alloc_obj) {
+            return Ok(obj);
+        }
+
+        if let Some(slab) = front.active.as_mut() {
+            // Objets libérés à distance : récupérés sans le verrou du cache.
+            // SAFETY: on détient le slab actif (verrou avant).
+            if unsafe { slab.drain_remote() } > 0 {
+                return Ok(slab.alloc_obj().expect("objets distants récupérés"));
+            }
+        }
+
+        let mut cache = self.shared.cache(idx).lock();
+        let mut provider = self.shared.shared_provider();
+        if let Some(slab) = front.active.take() {
+            // SAFETY: slab gelé par ce cache, plus utilisé par ce CPU.
+            unsafe { cache.unfreeze_slab(slab, &mut provider) };
+        }
+        let mut slab = cache.freeze_slab(&mut provider)?;
+        drop(cache);
+
+        // `freeze_slab` ne rend qu'un slab partiel, vide ou neuf.
+        let obj = slab.alloc_obj().expect("slab gelé sans objet libre");
+        front.active = Some(slab);
+        Ok(obj)
+    }
+
+    /// Libère un bloc sur le CPU courant.
+    ///
+    /// # Safety
+    /// - `ptr` doit provenir d’un `alloc(layout)` de CET allocator.
+    /// - `layout` doit être identique à celui utilisé lors de l'allocation.
+    /// - pas de double-free.
+    pub unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: préconditions identiques.
+        unsafe { self.dealloc_on(self.current_cpu(), ptr, layout) };
+    }
+
+    /// Comme `dealloc`, sur le CPU `cpu` (quel que soit le CPU qui a alloué).
+    ///
+    /// # Safety
+    /// Mêmes préconditions que `dealloc`.
+    pub unsafe fn dealloc_on(&self, cpu: usize, ptr: *mut u8, layout: Layout) {
+        let Some(nn) = NonNull::new(ptr) else { return };
+        // SAFETY: préconditions identiques.
+        let res = unsafe { self.try_dealloc_on(cpu, nn, layout) };
+        debug_assert!(res.is_ok(), "dealloc: {res:?}");
+    }
+
+    /// Comme `dealloc_on`, avec la raison de l'échec (voir `SlabAllocator::try_dealloc`).
+    ///
+    /// # Safety
+    /// - `ptr` doit provenir d’un `alloc` de CET allocator, non encore libéré.
+    pub unsafe fn try_dealloc_on(
+        &self,
+        cpu: usize,
+        ptr: NonNull<u8>,
+        layout: Layout,
+    ) -> Result<(), AllocError> {
+        let Route::Class(idx) = route(self.shared.classes(), layout)? else {
+            // SAFETY: préconditions identiques.
+            return unsafe { self.shared.try_dealloc(ptr, layout) };
+        };
+
+        {
+            let mut front = self.fronts[cpu % CPUS][idx].lock();
+            if let Some(slab) = front.active.as_mut() {
+                if slab.page_base() == (ptr.as_ptr() as usize & !(PAGE_SIZE - 1)) as *mut u8 {
+                    // SAFETY: `ptr` appartient au slab actif de ce CPU, qu'on détient.
+                    unsafe { slab.free(ptr) };
+                    return Ok(());
+                }
+            }
+        }
+
+        // Slab gelé par un autre CPU : pile `remote`, sans verrou.
+        // SAFETY: `ptr` vient de cet allocateur => sa page commence par un header.
+        if let Some(slab) = unsafe { Slab::from_obj(ptr) } {
+            // SAFETY: `ptr` appartient à ce slab et n'est pas déjà libéré.
+            if unsafe { slab.free_remote(ptr) } {
+                return Ok(());
+            }
+        }
+
+        // Slab dans les listes du cache (ou dégelé entre-temps).
+        let mut provider = self.shared.shared_provider();
+        // SAFETY: ptr vient de ce cache (même route), pages issues de `provider`.
+        unsafe { self.shared.cache(idx).lock().try_dealloc(ptr, &mut provider) }
+    }
+
+    /// Rend au cache partagé les slabs actifs de `cpu` (fin de thread, CPU
+    /// mis hors ligne, ...).
+    pub fn flush(&self, cpu: usize) {
+        let mut provider = self.shared.shared_provider();
+        for (idx, front) in self.fronts[cpu % CPUS].iter().enumerate() {
+            let mut front = front.lock();
+            if let Some(slab) = front.active.take() {
+                // SAFETY: slab gelé par ce cache, retiré du CPU sous son verrou.
+                unsafe { self.shared.cache(idx).lock().unfreeze_slab(slab, &mut provider) };
+            }
+        }
+    }
+
+    /// Vide tous les caches avant puis rend au provider les slabs vides ;
+    /// retourne le nombre de pages rendues.
+    pub fn shrink(&self) -> usize {
+        for cpu in 0..CPUS {
+            self.flush(cpu);
+        }
+        self.shared.shrink()
+    }
+
+    /// Règle, pour chaque cache partagé, le nombre de slabs vides gardés en réserve.
+    pub fn set_empty_limit(&self, limit: usize) {
+        self.shared.set_empty_limit(limit);
+    }
+
+    /// Vrai si `ptr` pointe dans une page de cet allocateur, slabs actifs des
+    /// CPU compris (voir `ConcurrentSlabAllocator::owns`).
+    pub fn owns(&self, ptr: *const u8) -> bool {
+        self.shared.owns(ptr)
+    }
+
+    /// Nombre d'objets (petits et gros) actuellement alloués, slabs actifs compris.
+    ///
+    /// Photographie non atomique : les caches sont lus l'un après l'autre.
+    pub fn live_objects(&self) -> usize {
+        let mut live = 0;
+        for fronts in self.fronts.iter() {
+            for front in fronts.iter() {
+                let front = front.lock();
+                if let Some(slab) = front.active.as_ref() {
+                    live += slab.inuse().saturating_sub(slab.remote_count()) as usize;
+                }
+            }
+        }
+        live + self.shared.live_objects()
+    }
+}
+
+/// Démontage : les slabs actifs reviennent au cache, qui rend tout au provider.
+impl<P: PageProvider, const CPUS: usize, const N: usize> Drop for PerCpuSlabAllocator<P, CPUS, N> {
+    fn drop(&mut self) {
+        for cpu in 0..CPUS {
+            self.flush(cpu);
+        }
+    }
+}
+
+// SAFETY:
+// - `alloc` renvoie soit null, soit un bloc respectant `layout`.
+// - `dealloc` reçoit un ptr/layout issus de `alloc` (contrat de GlobalAlloc).
+// - chaque structure partagée est protégée par son verrou.
+unsafe impl<P: PageProvider + Send, const CPUS: usize, const N: usize> GlobalAlloc
+    for PerCpuSlabAllocator<P, CPUS, N>
+{
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        PerCpuSlabAllocator::alloc(self, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        PerCpuSlabAllocator::alloc_zeroed(self, layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: précondition de GlobalAlloc::dealloc (ptr issu de alloc(layout)).
+        unsafe { PerCpuSlabAllocator::dealloc(self, ptr, layout) };
+    }
+}
+
+/// CPU courant par défaut : un index par thread avec `std`.
+#[cfg(feature = "std")]
+pub(crate) fn default_cpu_id() -> usize {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    std::thread_local! {
+        static ID: usize = NEXT.fetch_add(1, Ordering::Relaxed);
+    }
+    // Pendant la destruction du thread, on retombe sur le CPU 0.
+    ID.try_with(|id| *id).unwrap_or(0)
+}
+
+/// CPU courant par défaut sans `std` : 0 (voir `with_cpu_id`).
+#[cfg(not(feature = "std"))]
+pub(crate) fn default_cpu_id() -> usize {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(miri)]
+    type Prov = crate::page_provider::TestPageProvider;
+    #[cfg(not(miri))]
+    type Prov = crate::page_provider::StaticPageProvider<16>;
+
+    #[test]
+    fn remote_frees_return_to_the_owner_on_refill() {
+        let a: PerCpuSlabAllocator<Prov, 2> = PerCpuSlabAllocator::new(Prov::new());
+        let layout = Layout::from_size_align(1024, 8).unwrap();
+
+        // Le CPU 0 vide son slab actif (3 objets de 1024 par page).
+        let objs: [*mut u8; 3] = core::array::from_fn(|_| a.alloc_on(0, layout));
+        let page = objs[0] as usize & !(PAGE_SIZE - 1);
+        assert!(objs.iter().all(|&p| p as usize & !(PAGE_SIZE - 1) == page));
+
+        // Libérés par le CPU 1 : ils attendent dans la liste `remote`.
+        for &p in objs.iter() {
+            unsafe { a.dealloc_on(1, p, layout) };
+        }
+        assert_eq!(a.live_objects(), 0);
+
+        // La recharge du CPU 0 les récupère au lieu de prendre une autre page.
+        let again = a.alloc_on(0, layout);
+        assert_eq!(again as usize & !(PAGE_SIZE - 1), page);
+        unsafe { a.dealloc_on(0, again, layout) };
+
+        assert_eq!(a.shrink(), 1);
+        assert_eq!(a.live_objects(), 0);
+    }
+
+    #[test]
+    fn lock_free_remote_frees_are_reused_at_once() {
+        let a: PerCpuSlabAllocator<Prov, 2> = PerCpuSlabAllocator::new(Prov::new()).with_lock_free_freelist();
+        let layout = Layout::from_size_align(1024, 8).unwrap();
+
+        let p = a.alloc_on(0, layout);
+        let q = a.alloc_on(0, layout);
+        assert_eq!(p as usize & !(PAGE_SIZE - 1), q as usize & !(PAGE_SIZE - 1));
+
+        // Libéré par le CPU 1 : directement dans la freelist du slab actif,
+        // repris avant qu'il soit épuisé.
+        unsafe { a.dealloc_on(1, p, layout) };
+        assert_eq!(a.live_objects(), 1);
+        assert_eq!(a.alloc_on(0, layout), p);
+
+        unsafe {
+            a.dealloc_on(1, p, layout);
+            a.dealloc_on(0, q, layout);
+        }
+        assert_eq!(a.shrink(), 1);
+        assert_eq!(a.live_objects(), 0);
+    }
+}
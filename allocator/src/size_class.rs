@@ -0,0 +1,227 @@
+//! Tables de size classes.
+//!
+//! Une table est une suite strictement croissante de tailles d'objets ; chaque
+//! classe devient un `Cache`. Les objets d'une classe sont alignés sur la plus
+//! grande puissance de 2 qui divise sa taille (24 -> 8, 48 -> 16, 64 -> 64).
+//!
+//! La recherche de classe est en O(1) : une table précalculée donne, pour
+//! chaque granule de `GRANULE` bytes, l'index de la première classe assez grande.
+
+use core::mem;
+
+use crate::page_provider::PAGE_SIZE;
+use crate::slab::Slab;
+
+/// Tailles par défaut (puissances de 2, 8..2048).
+pub const DEFAULT_CLASS_SIZES: [usize; 9] = [8, 16, 32, 64, 128, 256, 512, 1024, 2048];
+
+/// Table par défaut, validée à la compilation.
+pub const DEFAULT_SIZE_CLASSES: SizeClasses<9> = SizeClasses::new_or_panic(DEFAULT_CLASS_SIZES);
+
+/// Tailles "fines" (4 classes par puissance de 2, façon jemalloc) :
+/// la fragmentation interne reste sous ~25% (257 bytes -> 320 au lieu de 512).
+pub const FINE_CLASS_SIZES: [usize; 26] = [
+    8, 16, 24, 32, 48, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384, 448, 512, 640, 768,
+    896, 1024, 1280, 1536, 1792, 2048,
+];
+
+/// Table fine, validée à la compilation.
+pub const FINE_SIZE_CLASSES: SizeClasses<26> = SizeClasses::new_or_panic(FINE_CLASS_SIZES);
+
+/// Granularité de la table de lookup (toutes les classes en sont multiples).
+const GRANULE: usize = mem::size_of::<usize>();
+
+/// Une entrée par granule jusqu'à PAGE_SIZE (aucune classe ne l'atteint).
+const LOOKUP_LEN: usize = PAGE_SIZE / GRANULE;
+
+/// Raison du rejet d'une table de size classes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SizeClassError {
+    /// Table vide.
+    Empty,
+    /// La classe `index` n'est pas un multiple de la taille d'un mot
+    /// (la freelist stocke un pointeur aligné dans chaque objet libre).
+    Misaligned { index: usize },
+    /// La classe `index` n'est pas strictement plus grande que la précédente.
+    NotIncreasing { index: usize },
+    /// La classe `index` ne tient pas dans une page avec le header de slab.
+    TooLarge { index: usize },
+    /// Plus de 255 classes (index stockés sur un `u8`).
+    TooMany,
+}
+
+/// Table de `N` size classes validée.
+#[derive(Copy, Clone, Debug)]
+pub struct SizeClasses<const N: usize> {
+    sizes: [usize; N],
+    /// `lookup[g]` = index de la première classe >= `g * GRANULE`.
+    lookup: [u8; LOOKUP_LEN],
+}
+
+impl<const N: usize> SizeClasses<N> {
+    /// Valide une table de tailles.
+    pub const fn new(sizes: [usize; N]) -> Result<Self, SizeClassError> {
+        if N == 0 {
+            return Err(SizeClassError::Empty);
+        }
+        if N > u8::MAX as usize {
+            return Err(SizeClassError::TooMany);
+        }
+
+        let mut i = 0;
+        while i < N {
+            let size = sizes[i];
+            if size == 0 || !size.is_multiple_of(GRANULE) {
+                return Err(SizeClassError::Misaligned { index: i });
+            }
+            if i > 0 && size <= sizes[i - 1] {
+                return Err(SizeClassError::NotIncreasing { index: i });
+            }
+            if !Slab::fits(size, class_align(size)) {
+                return Err(SizeClassError::TooLarge { index: i });
+            }
+            i += 1;
+        }
+
+        // Table de lookup : granules au-delà de la dernière classe => N (aucune).
+        let mut lookup = [N as u8; LOOKUP_LEN];
+        let mut g = 0;
+        let mut idx = 0;
+        while g < LOOKUP_LEN {
+            while idx < N && sizes[idx] < g * GRANULE {
+                idx += 1;
+            }
+            lookup[g] = idx as u8;
+            g += 1;
+        }
+
+        Ok(Self { sizes, lookup })
+    }
+
+    /// Comme `new`, mais panique (à la compilation dans un contexte `const`)
+    /// si la table est invalide.
+    pub const fn new_or_panic(sizes: [usize; N]) -> Self {
+        match Self::new(sizes) {
+            Ok(classes) => classes,
+            Err(_) => panic!("invalid size class table"),
+        }
+    }
+
+    /// Tailles des classes.
+    pub const fn sizes(&self) -> &[usize; N] {
+        &self.sizes
+    }
+
+    /// Taille de la classe `idx`.
+    #[inline]
+    pub const fn size(&self, idx: usize) -> usize {
+        self.sizes[idx]
+    }
+
+    /// Alignement garanti des objets de la classe `idx`.
+    #[inline]
+    pub const fn align(&self, idx: usize) -> usize {
+        class_align(self.sizes[idx])
+    }
+
+    /// Plus grande taille servie par un cache.
+    #[inline]
+    pub const fn max_size(&self) -> usize {
+        self.sizes[N - 1]
+    }
+
+    /// Plus petite classe pouvant contenir `size` bytes (O(1), sans parcours).
+    #[inline]
+    pub fn index_for(&self, size: usize) -> Option<usize> {
+        if size > self.max_size() {
+            return None;
+        }
+        // Les classes sont multiples de GRANULE : la première classe >= size
+        // est aussi la première >= size arrondi au granule supérieur.
+        Some(self.lookup[size.div_ceil(GRANULE)] as usize)
+    }
+
+    /// Plus petite classe qui satisfait à la fois `size` et `align`.
+    #[inline]
+    pub fn index_for_layout(&self, size: usize, align: usize) -> Option<usize> {
+        let first = self.index_for(size)?;
+        (first..N).find(|&idx| self.align(idx) >= align)
+    }
+}
+
+/// Alignement des objets d'une classe : plus grande puissance de 2 qui divise
+/// `size`, bornée par PAGE_SIZE.
+const fn class_align(size: usize) -> usize {
+    let align = 1usize << size.trailing_zeros();
+    if align > PAGE_SIZE {
+        PAGE_SIZE
+    } else {
+        align
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_table_is_valid() {
+        assert_eq!(DEFAULT_SIZE_CLASSES.sizes(), &DEFAULT_CLASS_SIZES);
+        assert_eq!(DEFAULT_SIZE_CLASSES.align(3), 64);
+        assert_eq!(DEFAULT_SIZE_CLASSES.index_for_layout(24, 64), Some(3));
+        assert_eq!(DEFAULT_SIZE_CLASSES.index_for_layout(4096, 8), None);
+    }
+
+    #[test]
+    fn custom_table_alignment() {
+        let c = SizeClasses::new([24, 48, 96, 192]).unwrap();
+        assert_eq!([c.align(0), c.align(1), c.align(2), c.align(3)], [8, 16, 32, 64]);
+
+        // 40 bytes alignés sur 32 : 48 (align 16) ne suffit pas => 96.
+        assert_eq!(c.index_for_layout(40, 32), Some(2));
+        assert_eq!(c.index_for_layout(100, 128), None);
+    }
+
+    #[test]
+    fn lookup_matches_linear_scan() {
+        fn check<const N: usize>(c: &SizeClasses<N>) {
+            for size in 0..=PAGE_SIZE {
+                let linear = c.sizes().iter().position(|&s| s >= size);
+                assert_eq!(c.index_for(size), linear, "size={size}");
+            }
+        }
+        check(&DEFAULT_SIZE_CLASSES);
+        check(&FINE_SIZE_CLASSES);
+        check(&SizeClasses::new([24, 48, 96, 192]).unwrap());
+    }
+
+    #[test]
+    fn fine_table_limits_fragmentation() {
+        let c = &FINE_SIZE_CLASSES;
+        assert_eq!(c.size(c.index_for(257).unwrap()), 320);
+        assert_eq!(c.size(c.index_for(1025).unwrap()), 1280);
+
+        // Au-delà de 48 bytes (espacement d'un quart), la perte reste < 25%.
+        for size in 49..=c.max_size() {
+            let class = c.size(c.index_for(size).unwrap());
+            assert!((class - size) * 4 < class, "size={size} class={class}");
+        }
+    }
+
+    #[test]
+    fn invalid_tables_are_rejected() {
+        assert_eq!(SizeClasses::<0>::new([]).unwrap_err(), SizeClassError::Empty);
+        assert_eq!(
+            SizeClasses::new([8, 12]).unwrap_err(),
+            SizeClassError::Misaligned { index: 1 }
+        );
+        assert_eq!(
+            SizeClasses::new([16, 16]).unwrap_err(),
+            SizeClassError::NotIncreasing { index: 1 }
+        );
+        assert_eq!(
+            SizeClasses::new([8, 4088]).unwrap_err(),
+            SizeClassError::TooLarge { index: 1 }
+        );
+    }
+}
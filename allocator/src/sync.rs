@@ -0,0 +1,126 @@
+//! Primitives de synchronisation minimales (no_std).
+//!
+//! Un simple spinlock suffit pour protéger l'allocateur : les sections
+//! critiques sont courtes et ne bloquent jamais.
+
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Spinlock protégeant une valeur `T`.
+pub struct SpinLock<T> {
+    locked: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+// SAFETY: l'accès à `data` est sérialisé par `locked`, donc partager le lock
+// entre threads revient à transférer `T` d'un thread à l'autre.
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+unsafe impl<T: Send> Send for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    /// Crée un spinlock déverrouillé (utilisable dans un `static`).
+    pub const fn new(data: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Attend activement que le lock soit libre puis le prend.
+    pub fn lock(&self) -> SpinLockGuard<'_, T> {
+        loop {
+            if self
+                .locked
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                return SpinLockGuard { lock: self };
+            }
+
+            // Attente en lecture seule pour ne pas saturer la ligne de cache.
+            while self.locked.load(Ordering::Relaxed) {
+                core::hint::spin_loop();
+            }
+        }
+    }
+
+    /// Accès direct sans verrouiller : l'emprunt exclusif garantit l'absence de concurrence.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Consomme le lock et rend la valeur protégée.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+/// Garde RAII : le lock est relâché au drop.
+pub struct SpinLockGuard<'a, T> {
+    lock: &'a SpinLock<T>,
+}
+
+impl<T> Deref for SpinLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: la garde n'existe que si le lock est pris => accès exclusif.
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T> DerefMut for SpinLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: la garde n'existe que si le lock est pris => accès exclusif.
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T> Drop for SpinLockGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+/// Verrou des structures partagées de `ConcurrentSlabAllocator` : `SpinLock`
+/// par défaut (no_std), `std::sync::Mutex` avec la feature `std-mutex` (un
+/// thread en attente dort au lieu de tourner).
+#[cfg(not(feature = "std-mutex"))]
+pub type Lock<T> = SpinLock<T>;
+
+/// Verrou des structures partagées de `ConcurrentSlabAllocator` (voir `MutexLock`).
+#[cfg(feature = "std-mutex")]
+pub type Lock<T> = MutexLock<T>;
+
+/// `std::sync::Mutex` avec la même interface que `SpinLock`.
+///
+/// L'empoisonnement est ignoré : les sections critiques de l'allocateur ne
+/// paniquent pas en laissant une structure à moitié modifiée.
+#[cfg(feature = "std-mutex")]
+pub struct MutexLock<T>(std::sync::Mutex<T>);
+
+#[cfg(feature = "std-mutex")]
+impl<T> MutexLock<T> {
+    /// Crée un mutex déverrouillé (utilisable dans un `static`).
+    pub const fn new(data: T) -> Self {
+        Self(std::sync::Mutex::new(data))
+    }
+
+    /// Prend le mutex (bloquant).
+    pub fn lock(&self) -> std::sync::MutexGuard<'_, T> {
+        self.0.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Accès direct sans verrouiller.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.0.get_mut().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Atomiques des structures sans verrou (`RemoteList`) : celles de `loom`
+/// sous `--cfg loom` pour explorer les entrelacements, sinon celles de `core`.
+#[cfg(loom)]
+pub(crate) use loom::sync::atomic::{AtomicU16, AtomicUsize};
+#[cfg(not(loom))]
+pub(crate) use core::sync::atomic::{AtomicU16, AtomicUsize};
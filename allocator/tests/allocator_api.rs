@@ -0,0 +1,97 @@
+//! Collections std (`allocator-api2`) sur `&LockedSlabAllocator`.
+#![cfg(feature = "allocator-api2")]
+
+use allocator_api2::alloc::{Allocator, Layout};
+use allocator_api2::boxed::Box;
+use allocator_api2::vec::Vec;
+
+use allocator::LockedSlabAllocator;
+
+#[cfg(not(miri))]
+use allocator::page_provider::StaticPageProvider;
+
+#[cfg(miri)]
+use allocator::page_provider::TestPageProvider;
+
+#[cfg(not(miri))]
+type Prov = StaticPageProvider<64>;
+#[cfg(miri)]
+type Prov = TestPageProvider;
+
+#[test]
+fn vec_grows_through_classes_and_pages() {
+    let slab = LockedSlabAllocator::new(Prov::new());
+
+    let mut v: Vec<u64, _> = Vec::new_in(&slab);
+    for i in 0..2000u64 {
+        v.push(i);
+    }
+    // 16000 bytes : passé par toutes les classes puis par les pages.
+    assert!(v.iter().copied().eq(0..2000));
+
+    v.truncate(10);
+    v.shrink_to_fit();
+    assert!(v.iter().copied().eq(0..10));
+
+    drop(v);
+    assert_eq!(slab.lock().live_objects(), 0);
+}
+
+#[test]
+fn boxes_and_nested_vecs() {
+    let slab = LockedSlabAllocator::new(Prov::new());
+
+    let b = Box::new_in([7u32; 100], &slab);
+    assert!(b.iter().all(|&x| x == 7));
+
+    let mut outer: Vec<Vec<u8, _>, _> = Vec::new_in(&slab);
+    for i in 0..32u8 {
+        let mut inner = Vec::with_capacity_in(i as usize, &slab);
+        inner.extend(core::iter::repeat_n(i, i as usize));
+        outer.push(inner);
+    }
+    for (i, inner) in outer.iter().enumerate() {
+        assert_eq!(inner.len(), i);
+        assert!(inner.iter().all(|&x| x as usize == i));
+    }
+
+    drop(outer);
+    drop(b);
+    assert_eq!(slab.lock().live_objects(), 0);
+}
+
+#[test]
+fn allocate_zeroed_and_grow_shrink() {
+    let slab = LockedSlabAllocator::new(Prov::new());
+    let a = &slab;
+
+    // Salit un bloc puis le rend : allocate_zeroed doit quand même rendre des zéros.
+    let dirty = Layout::from_size_align(64, 8).unwrap();
+    let p = a.allocate(dirty).unwrap().cast::<u8>();
+    unsafe {
+        p.as_ptr().write_bytes(0xFF, 64);
+        a.deallocate(p, dirty);
+    }
+
+    let block = a.allocate_zeroed(dirty).unwrap();
+    let bytes = unsafe { block.as_ref() };
+    assert!(bytes.iter().all(|&b| b == 0));
+
+    let ptr = block.cast::<u8>();
+    unsafe { ptr.as_ptr().write_bytes(0x5A, 64) };
+
+    let big = Layout::from_size_align(5000, 8).unwrap();
+    let grown = unsafe { a.grow(ptr, dirty, big) }.unwrap();
+    assert_eq!(grown.len(), 5000);
+    let grown = grown.cast::<u8>();
+    let kept = unsafe { core::slice::from_raw_parts(grown.as_ptr(), 64) };
+    assert!(kept.iter().all(|&b| b == 0x5A));
+
+    let small = Layout::from_size_align(16, 8).unwrap();
+    let shrunk = unsafe { a.shrink(grown, big, small) }.unwrap().cast::<u8>();
+    let kept = unsafe { core::slice::from_raw_parts(shrunk.as_ptr(), 16) };
+    assert!(kept.iter().all(|&b| b == 0x5A));
+
+    unsafe { a.deallocate(shrunk, small) };
+    assert_eq!(slab.lock().live_objects(), 0);
+}
@@ -1,6 +1,6 @@
 use core::alloc::Layout;
 
-use allocator::SlabAllocator;
+use allocator::{AllocError, SizeClasses, SlabAllocator};
 
 #[cfg(miri)]
 use allocator::PageProvider;
@@ -36,27 +36,217 @@ fn alloc_free_reuse_same_sizeclass() {
 }
 
 #[test]
-fn unsupported_size_returns_null() {
+fn large_sizes_are_served() {
     #[cfg(not(miri))]
     let provider = StaticPageProvider::<N_PAGES>::new();
     #[cfg(miri)]
     let provider = TestPageProvider::new();
     let mut a = SlabAllocator::new(provider);
 
-    let layout = Layout::from_size_align(4096, 8).unwrap();
-    let p = a.alloc(layout);
-    assert!(p.is_null());
+    let small = Layout::from_size_align(3000, 8).unwrap();
+    let big = Layout::from_size_align(64 * 1024, 16).unwrap();
+
+    let p = a.alloc(small);
+    let q = a.alloc(big);
+    assert!(!p.is_null());
+    assert!(!q.is_null());
+    assert_ne!(p, q);
+
+    unsafe {
+        core::ptr::write_bytes(p, 0x11, small.size());
+        core::ptr::write_bytes(q, 0x22, big.size());
+        assert_eq!(*p.add(small.size() - 1), 0x11);
+        assert_eq!(*q.add(big.size() - 1), 0x22);
+
+        a.dealloc(p, small);
+        a.dealloc(q, big);
+    }
+}
+
+#[test]
+fn large_allocation_oom_returns_null() {
+    #[cfg(not(miri))]
+    let provider = StaticPageProvider::<4>::new();
+    #[cfg(miri)]
+    let provider = LimitedProvider::new(4);
+    let mut a = SlabAllocator::new(provider);
+
+    // 1 page pour les métadonnées + 3 pages de données : il ne reste rien pour 5 pages.
+    let layout = Layout::from_size_align(5 * 4096, 8).unwrap();
+    assert!(a.alloc(layout).is_null());
+
+    let fits = Layout::from_size_align(3 * 4096, 8).unwrap();
+    let p = a.alloc(fits);
+    assert!(!p.is_null());
+    unsafe { a.dealloc(p, fits) };
+}
+
+#[test]
+fn try_api_reports_failure_reasons() {
+    #[cfg(not(miri))]
+    let provider = StaticPageProvider::<4>::new();
+    #[cfg(miri)]
+    let provider = LimitedProvider::new(4);
+    let mut a = SlabAllocator::new(provider);
+
+    let huge_align = Layout::from_size_align(64, 8192).unwrap();
+    assert_eq!(a.try_alloc(huge_align), Err(AllocError::AlignmentTooLarge { align: 8192 }));
+
+    let too_big = Layout::from_size_align(5 * 4096, 8).unwrap();
+    assert_eq!(a.try_alloc(too_big), Err(AllocError::OutOfPages));
+
+    let small = Layout::from_size_align(16, 8).unwrap();
+    let p = a.try_alloc(small).expect("small");
+
+    // Mauvais layout au dealloc : détecté, rien n'est libéré.
+    let other = Layout::from_size_align(512, 8).unwrap();
+    assert_eq!(unsafe { a.try_dealloc(p, other) }, Err(AllocError::WrongCache));
+    let big = Layout::from_size_align(8192, 8).unwrap();
+    assert_eq!(unsafe { a.try_dealloc(p, big) }, Err(AllocError::UnknownLargeAllocation));
+    assert_eq!(a.live_objects(), 1);
+
+    assert_eq!(unsafe { a.try_dealloc(p, small) }, Ok(()));
+    assert_eq!(a.live_objects(), 0);
+}
+
+#[test]
+fn free_without_layout_and_usable_size() {
+    #[cfg(not(miri))]
+    let provider = StaticPageProvider::<N_PAGES>::new();
+    #[cfg(miri)]
+    let provider = TestPageProvider::new();
+    let mut a = SlabAllocator::new(provider);
+
+    // (taille, alignement, taille utilisable attendue)
+    let cases = [(24, 8, 32), (24, 64, 64), (100, 8, 128), (2048, 8, 2048), (3000, 8, 4096), (9000, 8, 12288)];
+    let mut ptrs = [core::ptr::null_mut(); 6];
+    for (slot, &(size, align, usable)) in ptrs.iter_mut().zip(cases.iter()) {
+        *slot = a.alloc(Layout::from_size_align(size, align).unwrap());
+        assert!(!slot.is_null());
+        assert_eq!(unsafe { a.usable_size(*slot) }, Some(usable), "size={size} align={align}");
+    }
+    assert_eq!(a.live_objects(), cases.len());
+
+    for &p in &ptrs {
+        unsafe { a.free(p) };
+    }
+    assert_eq!(a.live_objects(), 0);
+
+    // Objet d'un cache nommé : pas une size class de l'allocateur.
+    let h = a.create_cache("obj", 40, 8, allocator::CacheFlags::NONE).unwrap();
+    let p = core::ptr::NonNull::new(a.cache_alloc(h)).unwrap();
+    assert_eq!(unsafe { a.try_free(p) }, Err(AllocError::WrongCache));
+    unsafe { a.cache_free(h, p.as_ptr()) };
+}
+
+/// Provider qui ne sait pas quelles pages il a fournies (`owns_page` par
+/// défaut) : force le parcours des slabs dans `owns`.
+struct Opaque<P>(P);
+
+impl<P: allocator::PageProvider> allocator::PageProvider for Opaque<P> {
+    fn alloc_page(&mut self) -> Option<core::ptr::NonNull<u8>> {
+        self.0.alloc_page()
+    }
+
+    fn dealloc_page(&mut self, ptr: core::ptr::NonNull<u8>) {
+        self.0.dealloc_page(ptr)
+    }
+
+    fn alloc_pages(&mut self, count: usize) -> Option<core::ptr::NonNull<u8>> {
+        self.0.alloc_pages(count)
+    }
+
+    fn dealloc_pages(&mut self, ptr: core::ptr::NonNull<u8>, count: usize) {
+        self.0.dealloc_pages(ptr, count)
+    }
+}
+
+fn check_owns<P: allocator::PageProvider>(mut a: SlabAllocator<P>) {
+    let small = Layout::from_size_align(48, 8).unwrap();
+    let big = Layout::from_size_align(3 * 4096, 8).unwrap();
+    let p = a.alloc(small);
+    let b = a.alloc(big);
+    let h = a.create_cache("owned", 40, 8, allocator::CacheFlags::NONE).unwrap();
+    let n = a.cache_alloc(h);
+    assert!(!p.is_null() && !b.is_null() && !n.is_null());
+
+    assert!(a.owns(p));
+    assert!(a.owns(p.wrapping_add(40)));
+    // Zone du header de slab.
+    assert!(a.owns(p.wrapping_sub(p as usize % 4096)));
+    assert!(a.owns(b.wrapping_add(2 * 4096 + 100)));
+    assert!(a.owns(n));
+
+    let local = 0u8;
+    assert!(!a.owns(&local));
+    assert!(!a.owns(core::ptr::null()));
+
+    // Page du provider prêtée à un autre utilisateur : pas à l'allocateur.
+    let foreign = a.provider_mut().alloc_page().expect("page");
+    assert!(!a.owns(foreign.as_ptr()));
+    assert!(!a.owns(foreign.as_ptr().wrapping_add(100)));
+    a.provider_mut().dealloc_page(foreign);
+
+    unsafe {
+        a.dealloc(b, big);
+        a.cache_free(h, n);
+    }
+    assert!(!a.owns(b));
+    assert!(a.owns(p));
+    unsafe { a.dealloc(p, small) };
+    a.shrink();
+    assert!(!a.owns(p));
+}
+
+#[test]
+fn owns_recognises_every_kind_of_pointer() {
+    // Provider qui connaît ses pages, puis repli sur le parcours des slabs.
+    #[cfg(not(miri))]
+    {
+        check_owns(SlabAllocator::new(StaticPageProvider::<16>::new()));
+        check_owns(SlabAllocator::new(Opaque(StaticPageProvider::<16>::new())));
+    }
+    #[cfg(miri)]
+    {
+        check_owns(SlabAllocator::new(TestPageProvider::new()));
+        check_owns(SlabAllocator::new(Opaque(TestPageProvider::new())));
+    }
+}
+
+#[test]
+fn alignment_larger_than_class_is_honoured() {
+    #[cfg(not(miri))]
+    let provider = StaticPageProvider::<N_PAGES>::new();
+    #[cfg(miri)]
+    let provider = TestPageProvider::new();
+    let mut a = SlabAllocator::new(provider);
+
+    let mut live = [(core::ptr::null_mut(), Layout::new::<u8>()); 8];
+    let cases = [(32, 64), (24, 64), (8, 128), (100, 512), (1, 2048), (32, 4096), (3000, 4096), (64, 1024)];
+
+    for (slot, &(size, align)) in live.iter_mut().zip(cases.iter()) {
+        let layout = Layout::from_size_align(size, align).unwrap();
+        let p = a.alloc(layout);
+        assert!(!p.is_null(), "size={size} align={align}");
+        assert_eq!((p as usize) % align, 0, "size={size} align={align}");
+        unsafe { core::ptr::write_bytes(p, 0xCD, size) };
+        *slot = (p, layout);
+    }
+
+    for &(p, layout) in &live {
+        unsafe { a.dealloc(p, layout) };
+    }
 }
 
 #[test]
-fn alignment_too_large_returns_null() {
+fn alignment_above_page_size_returns_null() {
     #[cfg(not(miri))]
     let provider = StaticPageProvider::<N_PAGES>::new();
     #[cfg(miri)]
     let provider = TestPageProvider::new();
     let mut a = SlabAllocator::new(provider);
 
-    let layout = Layout::from_size_align(32, 64).unwrap();
+    let layout = Layout::from_size_align(32, 8192).unwrap();
     let p = a.alloc(layout);
     assert!(p.is_null());
 }
@@ -73,10 +263,10 @@ fn alloc_multiple_then_free_all() {
 
     let mut ptrs = [core::ptr::null_mut(); 32];
 
-    for i in 0..ptrs.len() {
+    for slot in ptrs.iter_mut() {
         let p = a.alloc(layout);
         assert!(!p.is_null());
-        ptrs[i] = p;
+        *slot = p;
     }
 
     for &p in &ptrs {
@@ -152,6 +342,240 @@ fn allocator_oom_returns_null() {
     assert!(p_oom.is_null());
 }
 
+#[test]
+fn custom_size_classes_pack_objects_tightly() {
+    #[cfg(not(miri))]
+    let provider = StaticPageProvider::<N_PAGES>::new();
+    #[cfg(miri)]
+    let provider = TestPageProvider::new();
+    let classes = SizeClasses::new([24, 48, 96, 2048]).expect("valid table");
+    let mut a = SlabAllocator::with_classes(provider, classes);
+
+    for (size, stride) in [(24, 24), (17, 24), (40, 48), (96, 96)] {
+        let layout = Layout::from_size_align(size, 8).unwrap();
+        let p = a.alloc(layout);
+        let q = a.alloc(layout);
+        assert!(!p.is_null() && !q.is_null());
+        assert_eq!((q as usize).abs_diff(p as usize), stride, "size={size}");
+        unsafe {
+            a.dealloc(p, layout);
+            a.dealloc(q, layout);
+        }
+    }
+
+    // Au-delà de la dernière classe : chemin multi-pages.
+    let big = Layout::from_size_align(2049, 8).unwrap();
+    let p = a.alloc(big);
+    assert_eq!((p as usize) % 4096, 0);
+    unsafe { a.dealloc(p, big) };
+}
+
+#[test]
+fn fine_size_classes_reduce_waste() {
+    #[cfg(not(miri))]
+    let provider = StaticPageProvider::<N_PAGES>::new();
+    #[cfg(miri)]
+    let provider = TestPageProvider::new();
+    let mut a = SlabAllocator::new_fine(provider);
+
+    // 257 bytes : classe 320 (et non 512 avec la table par défaut).
+    let layout = Layout::from_size_align(257, 8).unwrap();
+    let p = a.alloc(layout);
+    let q = a.alloc(layout);
+    assert_eq!((q as usize).abs_diff(p as usize), 320);
+    unsafe {
+        a.dealloc(p, layout);
+        a.dealloc(q, layout);
+    }
+}
+
+#[test]
+fn realloc_in_place_then_moves_across_classes_and_pages() {
+    #[cfg(not(miri))]
+    let provider = StaticPageProvider::<N_PAGES>::new();
+    #[cfg(miri)]
+    let provider = TestPageProvider::new();
+    let mut a = SlabAllocator::new(provider);
+
+    let l40 = Layout::from_size_align(40, 8).unwrap();
+    let p = a.alloc(l40);
+    assert!(!p.is_null());
+    for i in 0..40 {
+        unsafe { p.add(i).write(i as u8) };
+    }
+
+    // 40 -> 64 : même classe (64), pointeur inchangé.
+    let q = unsafe { a.realloc(p, l40, 64) };
+    assert_eq!(q, p);
+
+    // 64 -> 300 : classe 512, données déplacées.
+    let l64 = Layout::from_size_align(64, 8).unwrap();
+    let r = unsafe { a.realloc(q, l64, 300) };
+    assert!(!r.is_null() && r != q);
+    assert!((0..40).all(|i| unsafe { r.add(i).read() } == i as u8));
+
+    // 300 -> 6000 : passage au chemin multi-pages.
+    let l300 = Layout::from_size_align(300, 8).unwrap();
+    let big = unsafe { a.realloc(r, l300, 6000) };
+    assert!(!big.is_null());
+    assert_eq!(big as usize % allocator::PAGE_SIZE, 0);
+    assert!((0..40).all(|i| unsafe { big.add(i).read() } == i as u8));
+
+    // 6000 -> 8000 : toujours 2 pages, sur place.
+    let l6000 = Layout::from_size_align(6000, 8).unwrap();
+    let big2 = unsafe { a.realloc(big, l6000, 8000) };
+    assert_eq!(big2, big);
+
+    // 8000 -> 24 : retour vers une classe.
+    let l8000 = Layout::from_size_align(8000, 8).unwrap();
+    let small = unsafe { a.realloc(big2, l8000, 24) };
+    assert!(!small.is_null());
+    assert!((0..24).all(|i| unsafe { small.add(i).read() } == i as u8));
+    assert_eq!(a.live_objects(), 1);
+
+    unsafe { a.dealloc(small, Layout::from_size_align(24, 8).unwrap()) };
+    assert_eq!(a.live_objects(), 0);
+}
+
+#[test]
+fn alloc_zeroed_clears_recycled_objects() {
+    #[cfg(not(miri))]
+    let provider = StaticPageProvider::<N_PAGES>::new();
+    #[cfg(miri)]
+    let provider = TestPageProvider::new();
+    let mut a = SlabAllocator::new(provider);
+
+    for size in [64, 10_000] {
+        let layout = Layout::from_size_align(size, 8).unwrap();
+        let p = a.alloc(layout);
+        assert!(!p.is_null());
+        unsafe {
+            p.write_bytes(0xFF, size);
+            a.dealloc(p, layout);
+        }
+
+        let z = a.alloc_zeroed(layout);
+        assert!(!z.is_null());
+        let bytes = unsafe { core::slice::from_raw_parts(z, size) };
+        assert!(bytes.iter().all(|&b| b == 0), "size={size}");
+        unsafe { a.dealloc(z, layout) };
+    }
+}
+
+/// Provider qui annonce des pages à zéro mais les remplit de 0xEE :
+/// rend visible quels objets `alloc_zeroed` efface réellement.
+struct ClaimsZeroed<P>(P);
+
+impl<P: allocator::PageProvider> allocator::PageProvider for ClaimsZeroed<P> {
+    const ZEROES_PAGES: bool = true;
+
+    fn alloc_page(&mut self) -> Option<core::ptr::NonNull<u8>> {
+        let page = self.0.alloc_page()?;
+        unsafe { page.as_ptr().write_bytes(0xEE, allocator::PAGE_SIZE) };
+        Some(page)
+    }
+
+    fn dealloc_page(&mut self, ptr: core::ptr::NonNull<u8>) {
+        self.0.dealloc_page(ptr)
+    }
+}
+
+#[test]
+fn alloc_zeroed_skips_fresh_objects_of_zeroed_pages() {
+    #[cfg(not(miri))]
+    let provider = StaticPageProvider::<N_PAGES>::new();
+    #[cfg(miri)]
+    let provider = TestPageProvider::new();
+    let mut a = SlabAllocator::new(ClaimsZeroed(provider));
+
+    let layout = Layout::from_size_align(32, 8).unwrap();
+    let p = a.alloc_zeroed(layout);
+    let q = a.alloc_zeroed(layout);
+    // Objets neufs : pas de memset (on voit le motif du "faux" provider).
+    assert!(unsafe { core::slice::from_raw_parts(p, 32) }.iter().all(|&b| b == 0xEE));
+    assert!(unsafe { core::slice::from_raw_parts(q, 32) }.iter().all(|&b| b == 0xEE));
+
+    // Objet recyclé (noeud de freelist écrit dedans) : effacé.
+    unsafe { a.dealloc(p, layout) };
+    let r = a.alloc_zeroed(layout);
+    assert_eq!(r, p);
+    assert!(unsafe { core::slice::from_raw_parts(r, 32) }.iter().all(|&b| b == 0));
+
+    unsafe {
+        a.dealloc(q, layout);
+        a.dealloc(r, layout);
+    }
+}
+
+#[cfg(not(miri))]
+#[test]
+fn shrink_returns_empty_slabs_to_provider() {
+    let mut a = SlabAllocator::new(StaticPageProvider::<N_PAGES>::new());
+    a.set_empty_limit(usize::MAX);
+
+    let layout = Layout::from_size_align(512, 8).unwrap();
+    let mut ptrs = [core::ptr::null_mut(); 40];
+    for slot in ptrs.iter_mut() {
+        *slot = a.alloc(layout);
+        assert!(!slot.is_null());
+    }
+    let peak = N_PAGES - a.provider_mut().free_pages();
+    assert!(peak >= 5);
+
+    for &p in &ptrs {
+        unsafe { a.dealloc(p, layout) };
+    }
+    // Réserve illimitée : les pages restent au chaud.
+    assert_eq!(N_PAGES - a.provider_mut().free_pages(), peak);
+
+    assert_eq!(a.shrink(), peak);
+    assert_eq!(a.provider_mut().free_pages(), N_PAGES);
+}
+
+#[cfg(not(miri))]
+#[test]
+fn default_policy_keeps_one_empty_slab() {
+    let mut a = SlabAllocator::new(StaticPageProvider::<N_PAGES>::new());
+
+    let layout = Layout::from_size_align(2048, 8).unwrap();
+    let mut ptrs = [core::ptr::null_mut(); 8];
+    for slot in ptrs.iter_mut() {
+        *slot = a.alloc(layout);
+    }
+    for &p in &ptrs {
+        unsafe { a.dealloc(p, layout) };
+    }
+
+    assert_eq!(N_PAGES - a.provider_mut().free_pages(), 1);
+}
+
+#[cfg(not(miri))]
+#[test]
+fn drop_returns_every_page() {
+    let mut provider = StaticPageProvider::<N_PAGES>::new();
+
+    {
+        let mut a = SlabAllocator::new(&mut provider);
+        let small = Layout::from_size_align(64, 8).unwrap();
+        let big = Layout::from_size_align(10_000, 8).unwrap();
+
+        let freed = a.alloc(small);
+        let mut leaked = [core::ptr::null_mut(); 100];
+        for slot in leaked.iter_mut() {
+            *slot = a.alloc(small);
+        }
+        let b = a.alloc(big);
+        assert!(!b.is_null());
+        unsafe { a.dealloc(freed, small) };
+
+        assert_eq!(a.live_objects(), 101);
+        assert!(a.provider_mut().free_pages() < N_PAGES);
+        // pas de dealloc : le drop doit tout rendre quand même
+    }
+
+    assert_eq!(provider.free_pages(), N_PAGES);
+}
+
 #[cfg(miri)]
 struct LimitedProvider {
     inner: TestPageProvider,
@@ -180,5 +604,59 @@ impl PageProvider for LimitedProvider {
         self.inner.dealloc_page(ptr);
         self.remaining += 1;
     }
+
+    fn alloc_pages(&mut self, count: usize) -> Option<core::ptr::NonNull<u8>> {
+        if count > self.remaining {
+            return None;
+        }
+        let p = self.inner.alloc_pages(count)?;
+        self.remaining -= count;
+        Some(p)
+    }
+
+    fn dealloc_pages(&mut self, ptr: core::ptr::NonNull<u8>, count: usize) {
+        self.inner.dealloc_pages(ptr, count);
+        self.remaining += count;
+    }
+
+    fn owns_page(&self, page: core::ptr::NonNull<u8>) -> Option<bool> {
+        self.inner.owns_page(page)
+    }
 }
 
+
+#[test]
+fn random_freelist_is_seeded_and_complete() {
+    use allocator::slab::FreelistRandom;
+
+    const OBJS: usize = 200;
+    let layout = Layout::from_size_align(64, 8).unwrap();
+
+    // Même graine : deux allocateurs distribuent les objets dans le même ordre
+    // (à la position dans la page près).
+    let mut orders = [[0usize; OBJS]; 2];
+    for order in orders.iter_mut() {
+        #[cfg(not(miri))]
+        let provider = StaticPageProvider::<N_PAGES>::new();
+        #[cfg(miri)]
+        let provider = TestPageProvider::new();
+        let mut a = SlabAllocator::new(provider).with_random_freelist(FreelistRandom::with_seed(0x5EED));
+
+        let mut ptrs = [core::ptr::null_mut::<u8>(); OBJS];
+        for (p, slot) in ptrs.iter_mut().zip(order.iter_mut()) {
+            *p = a.alloc(layout);
+            assert!(!p.is_null());
+            *slot = *p as usize % 4096;
+        }
+        // Chaque objet n'est distribué qu'une fois.
+        let mut sorted = ptrs;
+        sorted.sort_unstable();
+        assert!(sorted.windows(2).all(|w| w[0] != w[1]));
+
+        for p in ptrs {
+            unsafe { a.dealloc(p, layout) };
+        }
+    }
+    assert_eq!(orders[0], orders[1]);
+    assert!(!orders[0].windows(2).all(|w| w[0] < w[1]), "ordre non mélangé");
+}
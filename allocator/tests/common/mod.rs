@@ -0,0 +1,90 @@
+//! Outils partagés par les tests d'intégration : multi-thread (`concurrent`,
+//! `percpu`, `magazine`) et arrêts sur corruption (`hardened`).
+
+// Chaque test n'utilise qu'une partie de ces outils.
+#![allow(dead_code)]
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+#[cfg(not(miri))]
+pub const ROUNDS: usize = 20_000;
+#[cfg(miri)]
+pub const ROUNDS: usize = 200;
+
+pub const THREADS: usize = 8;
+
+/// Un index par thread, fourni par le test (comme le ferait un noyau).
+pub fn thread_index() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    thread_local! {
+        static ID: usize = NEXT.fetch_add(1, Ordering::Relaxed);
+    }
+    ID.with(|id| *id)
+}
+
+/// Graine du générateur du thread `id` (jamais nulle).
+pub fn seed(id: usize) -> u64 {
+    0x9E37_79B9_7F4A_7C15u64 ^ (id as u64 + 1)
+}
+
+/// Générateur xorshift (déterministe, un par thread).
+pub fn next(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Un thread alloue `ROUNDS` objets de `size` octets, un autre les vérifie
+/// puis les libère : toutes les libérations viennent d'un autre thread.
+pub fn producer_allocates_consumer_frees(
+    size: usize,
+    alloc: impl Fn() -> *mut u8 + Send,
+    free: impl Fn(*mut u8) + Send,
+) {
+    let (give, take) = mpsc::sync_channel::<usize>(256);
+    thread::scope(|s| {
+        s.spawn(move || {
+            for i in 0..ROUNDS {
+                let p = alloc();
+                assert!(!p.is_null(), "producteur: OOM");
+                unsafe { p.write_bytes(i as u8, size) };
+                give.send(p as usize).unwrap();
+            }
+        });
+        s.spawn(move || {
+            for (i, p) in take.into_iter().enumerate() {
+                let bytes = unsafe { core::slice::from_raw_parts(p as *const u8, size) };
+                assert!(bytes.iter().all(|&b| b == i as u8), "consommateur: corruption");
+                free(p as *mut u8);
+            }
+        });
+    });
+}
+
+/// Vérifie que `f` arrête le programme (abort, sans déroulement) avec `msg`
+/// sur stderr : le test `name` est relancé dans un processus fils, qui seul
+/// exécute `f`.
+pub fn assert_aborts(name: &str, msg: &str, f: impl FnOnce()) {
+    const CHILD: &str = "ALLOCATOR_ABORT_CHILD";
+    if std::env::var_os(CHILD).is_some() {
+        f();
+        panic!("corruption non détectée");
+    }
+    let out = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([name, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD, "1")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains(msg), "message absent : {stderr}");
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        assert_eq!(out.status.signal(), Some(6), "pas d'abort : {:?}", out.status);
+    }
+    #[cfg(not(unix))]
+    assert!(!out.status.success());
+}
@@ -0,0 +1,85 @@
+//! Stress multi-thread de `ConcurrentSlabAllocator`.
+
+mod common;
+
+use core::alloc::Layout;
+use std::thread;
+
+use allocator::page_provider::StaticPageProvider;
+use allocator::ConcurrentSlabAllocator;
+use common::{next, seed, ROUNDS, THREADS};
+
+const PAGES: usize = 512;
+
+fn churn(slab: &ConcurrentSlabAllocator<StaticPageProvider<PAGES>>, id: usize) {
+    const SIZES: [usize; 10] = [8, 16, 24, 48, 100, 256, 640, 1500, 2048, 5000];
+
+    let mut rng = seed(id);
+    let mut live: [Option<(*mut u8, Layout)>; 64] = [None; 64];
+
+    for _ in 0..ROUNDS {
+        let r = next(&mut rng);
+        let slot = &mut live[(r % 64) as usize];
+
+        match slot.take() {
+            Some((p, layout)) => {
+                // Le contenu n'a pas été touché par un autre thread.
+                let bytes = unsafe { core::slice::from_raw_parts(p, layout.size()) };
+                assert!(bytes.iter().all(|&b| b == id as u8), "thread {id}: corruption");
+                unsafe { slab.dealloc(p, layout) };
+            }
+            None => {
+                let size = SIZES[((r >> 8) % SIZES.len() as u64) as usize];
+                let layout = Layout::from_size_align(size, 8).unwrap();
+                let p = slab.alloc(layout);
+                assert!(!p.is_null(), "thread {id}: OOM");
+                assert_eq!(p as usize % 8, 0);
+                unsafe { p.write_bytes(id as u8, size) };
+                *slot = Some((p, layout));
+            }
+        }
+    }
+
+    for (p, layout) in live.into_iter().flatten() {
+        unsafe { slab.dealloc(p, layout) };
+    }
+}
+
+#[test]
+fn threads_share_the_allocator() {
+    static SLAB: ConcurrentSlabAllocator<StaticPageProvider<PAGES>> =
+        ConcurrentSlabAllocator::new(StaticPageProvider::new());
+
+    thread::scope(|s| {
+        for id in 0..THREADS {
+            s.spawn(move || churn(&SLAB, id));
+        }
+    });
+
+    assert_eq!(SLAB.live_objects(), 0);
+    SLAB.shrink();
+    assert_eq!(SLAB.lock_provider().free_pages(), PAGES);
+}
+
+#[test]
+fn warm_cache_allocates_without_the_provider_lock() {
+    static SLAB: ConcurrentSlabAllocator<StaticPageProvider<PAGES>> =
+        ConcurrentSlabAllocator::new(StaticPageProvider::new());
+
+    // Provider verrouillé (comme pendant le `grow` d'une autre classe) : un
+    // cache qui a encore des objets libres sert quand même, seul son propre
+    // verrou est pris.
+    let small = Layout::from_size_align(16, 8).unwrap();
+    let warm = SLAB.alloc(small);
+    assert!(!warm.is_null());
+
+    let guard = SLAB.lock_provider();
+    let other = thread::scope(|s| s.spawn(|| SLAB.alloc(small) as usize).join().unwrap());
+    drop(guard);
+
+    assert_ne!(other, 0);
+    unsafe {
+        SLAB.dealloc(warm, small);
+        SLAB.dealloc(other as *mut u8, small);
+    }
+}
@@ -0,0 +1,92 @@
+//! Programme complet tournant sur le `SlabAllocator` installé comme
+//! `#[global_allocator]` (pas de harness : tout passe par notre allocateur).
+
+use std::collections::BTreeMap;
+use std::string::String;
+use std::vec::Vec;
+
+use allocator::page_provider::StaticPageProvider;
+use allocator::LockedSlabAllocator;
+
+const N_PAGES: usize = 512;
+
+#[global_allocator]
+static GLOBAL: LockedSlabAllocator<StaticPageProvider<N_PAGES>> =
+    LockedSlabAllocator::new(StaticPageProvider::new());
+
+fn free_pages() -> usize {
+    GLOBAL.lock().provider_mut().free_pages()
+}
+
+fn vec_churn() {
+    for round in 0..64u64 {
+        let mut v: Vec<u64> = Vec::new();
+        for i in 0..200 {
+            v.push(i * round);
+        }
+        assert_eq!(v.iter().sum::<u64>(), round * (199 * 200 / 2));
+        v.truncate(10);
+        v.shrink_to_fit();
+        assert_eq!(v.len(), 10);
+    }
+}
+
+fn string_churn() {
+    let mut kept = Vec::new();
+    for i in 0..256 {
+        let mut s = String::new();
+        for j in 0..(i % 40) {
+            s.push_str(&j.to_string());
+        }
+        if i % 3 == 0 {
+            kept.push(s);
+        }
+    }
+    assert_eq!(kept.len(), 86);
+    assert!(kept.iter().all(|s| s.len() < 2048));
+}
+
+fn large_buffers() {
+    // Au-delà de 2048 bytes : chemin multi-pages.
+    for kib in [3usize, 8, 64] {
+        let mut buf = vec![0u8; kib * 1024];
+        buf[kib * 1024 - 1] = 7;
+        assert_eq!(buf.iter().map(|&b| b as usize).sum::<usize>(), 7);
+    }
+    let mut big: Vec<u64> = Vec::new();
+    for i in 0..10_000 {
+        big.push(i);
+    }
+    assert_eq!(big.len(), 10_000);
+}
+
+fn btreemap_churn() {
+    let mut m = BTreeMap::new();
+    for i in 0..2000u32 {
+        m.insert(i.wrapping_mul(2654435761) % 5000, i);
+    }
+    for i in 0..1000u32 {
+        m.remove(&(i.wrapping_mul(2654435761) % 5000));
+    }
+    assert_eq!(m.len(), 1000);
+    let mut prev = None;
+    for (&k, _) in m.iter() {
+        assert!(prev.is_none_or(|p| p < k));
+        prev = Some(k);
+    }
+}
+
+fn main() {
+    let before = free_pages();
+    assert!(before < N_PAGES, "le runtime doit déjà avoir alloué via le slab");
+
+    vec_churn();
+    string_churn();
+    large_buffers();
+    btreemap_churn();
+
+    // Des pages ont bien été consommées par le programme.
+    assert!(free_pages() < N_PAGES);
+
+    println!("global_alloc: ok ({} pages libres)", free_pages());
+}
@@ -0,0 +1,175 @@
+//! Corruptions détectées par `freelist-hardened` : chaque test vérifie, dans
+//! un processus fils, que le programme s'arrête (abort) au lieu de distribuer
+//! un objet forgé.
+#![cfg(all(feature = "freelist-hardened", not(miri)))]
+
+mod common;
+
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+use allocator::freelist::{AtomicFreeList, FreeList, NodeCodec, RemoteList};
+use allocator::page_provider::{StaticPageProvider, PAGE_SIZE};
+use allocator::{CacheFlags, SlabAllocator};
+use common::assert_aborts;
+
+/// Secret des listes construites à la main.
+const SECRET: usize = 0x5a5a_5a5a;
+
+/// Objets de 16 octets, à un offset multiple de 64 dans la page.
+const CODEC: NodeCodec = NodeCodec::new(0, 16, PAGE_SIZE / 16, SECRET);
+
+#[repr(C, align(64))]
+struct Objs([[u64; 2]; 4]);
+
+/// Page alignée pour `AtomicFreeList` (positions relatives à la page).
+#[repr(C, align(4096))]
+struct Page([u8; PAGE_SIZE]);
+
+/// Remplace le noeud encodé `node` (qui désigne `next`) par un noeud
+/// désignant `target` : `secret ^ adresse du noeud` s'annule, l'attaquant
+/// n'a besoin que de lire le noeud (use after free) et de connaître `next`.
+fn forge(node: *mut u8, next: usize, target: usize) {
+    unsafe {
+        let node = node.cast::<usize>();
+        node.write(node.read() ^ next ^ target);
+    }
+}
+
+/// Alloue deux objets de 32 octets, les libère, puis redirige le noeud de
+/// p2 (tête de liste, qui désigne p1) vers `target(p1)` et alloue : le
+/// décodage doit arrêter le programme.
+fn use_after_free_write(target: impl FnOnce(*mut u8) -> usize) {
+    let mut a = SlabAllocator::new(StaticPageProvider::<64>::new());
+
+    let layout = Layout::from_size_align(32, 8).unwrap();
+    let p1 = a.alloc(layout);
+    let p2 = a.alloc(layout);
+    unsafe {
+        a.dealloc(p1, layout);
+        a.dealloc(p2, layout);
+        forge(p2, p1 as usize, target(p1));
+    }
+    a.alloc(layout);
+}
+
+#[test]
+fn freelist_aborts_on_node_outside_the_page() {
+    assert_aborts("freelist_aborts_on_node_outside_the_page", "freelist corrompue", || {
+        use_after_free_write(|p1| p1 as usize + 4096 * 8)
+    });
+}
+
+#[test]
+fn freelist_aborts_on_node_inside_an_object() {
+    // Même page, mais pas au début d'un objet.
+    assert_aborts("freelist_aborts_on_node_inside_an_object", "freelist corrompue", || {
+        use_after_free_write(|p1| p1 as usize + 4)
+    });
+}
+
+#[test]
+fn freelist_aborts_on_trailing_partial_slot() {
+    // Deux objets de 1360 octets par slab : l'emplacement suivant le dernier
+    // commence dans la page mais la dépasse.
+    assert_aborts("freelist_aborts_on_trailing_partial_slot", "freelist corrompue", || {
+        let mut a = SlabAllocator::new(StaticPageProvider::<64>::new());
+        let h = a.create_cache("trailing", 1360, 8, CacheFlags::NONE).unwrap();
+
+        let p1 = a.cache_alloc(h);
+        let p2 = a.cache_alloc(h);
+        assert_eq!(p2 as usize, p1 as usize + 1360);
+        let trailing = p2 as usize + 1360;
+        assert!(!trailing.is_multiple_of(PAGE_SIZE) && trailing / PAGE_SIZE == p2 as usize / PAGE_SIZE);
+        unsafe {
+            a.cache_free(h, p1);
+            a.cache_free(h, p2);
+            forge(p2, p1 as usize, trailing);
+        }
+        a.cache_alloc(h);
+    });
+}
+
+#[test]
+fn pop_aborts_on_redirected_node() {
+    assert_aborts("pop_aborts_on_redirected_node", "freelist corrompue", || {
+        let mut objs = Objs([[0u64; 2]; 4]);
+        let ptrs: [NonNull<u8>; 4] = core::array::from_fn(|i| NonNull::from(&mut objs.0[i]).cast());
+
+        let mut list = FreeList::with_offset(8).with_codec(CODEC);
+        unsafe {
+            list.push(ptrs[0]);
+            list.push(ptrs[1]);
+            // Écriture après libération : l'attaquant vise une autre adresse.
+            forge(ptrs[1].add(8).as_ptr(), ptrs[0].as_ptr() as usize, 0xdead_0000);
+            list.pop();
+        }
+    });
+}
+
+#[test]
+fn pop_aborts_on_node_between_objects() {
+    assert_aborts("pop_aborts_on_node_between_objects", "freelist corrompue", || {
+        let mut objs = Objs([[0u64; 2]; 4]);
+        let ptrs: [NonNull<u8>; 4] = core::array::from_fn(|i| NonNull::from(&mut objs.0[i]).cast());
+
+        let mut list = FreeList::with_offset(8).with_codec(CODEC);
+        unsafe {
+            list.push(ptrs[0]);
+            list.push(ptrs[1]);
+            // Suivant dans la bonne page, mais au milieu d'un objet.
+            let next = ptrs[0].as_ptr() as usize;
+            forge(ptrs[1].add(8).as_ptr(), next, next + 4);
+            list.pop();
+        }
+    });
+}
+
+#[test]
+fn remote_drain_aborts_on_redirected_node() {
+    assert_aborts("remote_drain_aborts_on_redirected_node", "freelist corrompue", || {
+        let mut objs = Objs([[0u64; 2]; 4]);
+        let ptrs: [NonNull<u8>; 4] = core::array::from_fn(|i| NonNull::from(&mut objs.0[i]).cast());
+
+        let remote = RemoteList::with_offset(8).with_codec(CODEC);
+        remote.freeze();
+        unsafe {
+            remote.push(ptrs[0]);
+            remote.push(ptrs[1]);
+            forge(ptrs[1].add(8).as_ptr(), ptrs[0].as_ptr() as usize, 0xdead_0000);
+            remote.drain_into(&mut FreeList::with_offset(8).with_codec(CODEC), true);
+        }
+    });
+}
+
+#[test]
+fn shared_pop_aborts_past_capacity() {
+    assert_aborts("shared_pop_aborts_past_capacity", "freelist corrompue", || {
+        let mut page = Page([0; PAGE_SIZE]);
+        let base = NonNull::from(&mut page).cast::<u8>();
+
+        // 3 objets de 512 octets : l'index 3 tient encore dans la page, mais
+        // n'est pas un objet du slab.
+        let list = AtomicFreeList::new(64, NodeCodec::new(2048, 512, 3, SECRET));
+        unsafe {
+            list.fill(base, &[0, 1, 2]);
+            // Le noeud de l'objet 0 (tête) désigne l'index 3 (stocké + 1).
+            base.add(64).cast::<u16>().write((4 ^ SECRET) as u16);
+            list.pop(base);
+        }
+    });
+}
+
+#[test]
+fn push_aborts_on_immediate_double_free() {
+    assert_aborts("push_aborts_on_immediate_double_free", "double libération", || {
+        let mut objs = Objs([[0u64; 2]; 4]);
+        let p = NonNull::from(&mut objs.0[0]).cast();
+
+        let mut list = FreeList::new();
+        unsafe {
+            list.push(p);
+            list.push(p);
+        }
+    });
+}
@@ -0,0 +1,69 @@
+//! Stress multi-thread de `MagazineCache` : magazines échangés via le dépôt.
+
+mod common;
+
+use std::thread;
+
+use allocator::page_provider::StaticPageProvider;
+use allocator::{Cache, MagazineCache};
+use common::{next, seed, thread_index, ROUNDS, THREADS};
+
+const PAGES: usize = 256;
+const CPUS: usize = 4;
+const OBJ: usize = 96;
+
+type Mags = MagazineCache<StaticPageProvider<PAGES>, CPUS, 8>;
+
+#[test]
+fn threads_churn_through_shared_depot() {
+    static MAGS: Mags =
+        MagazineCache::new(Cache::new(OBJ, 8), StaticPageProvider::new()).with_cpu_id(thread_index);
+
+    thread::scope(|s| {
+        for id in 0..THREADS {
+            s.spawn(move || {
+                let mut rng = seed(id);
+                let mut live = [core::ptr::null_mut::<u8>(); 32];
+                for _ in 0..ROUNDS {
+                    let r = next(&mut rng);
+                    let slot = &mut live[(r % 32) as usize];
+                    if slot.is_null() {
+                        let p = MAGS.alloc();
+                        assert!(!p.is_null(), "thread {id}: OOM");
+                        unsafe { p.write_bytes(id as u8, OBJ) };
+                        *slot = p;
+                    } else {
+                        let bytes = unsafe { core::slice::from_raw_parts(*slot, OBJ) };
+                        assert!(bytes.iter().all(|&b| b == id as u8), "thread {id}: corruption");
+                        unsafe { MAGS.free(*slot) };
+                        *slot = core::ptr::null_mut();
+                    }
+                }
+                for p in live.into_iter().filter(|p| !p.is_null()) {
+                    unsafe { MAGS.free(p) };
+                }
+            });
+        }
+    });
+
+    assert_eq!(MAGS.live_objects(), 0);
+    MAGS.shrink();
+    assert_eq!(MAGS.lock_cache().live_objects(), 0);
+    assert_eq!(MAGS.lock_provider().free_pages(), PAGES);
+}
+
+#[test]
+fn producer_allocates_consumer_frees() {
+    static MAGS: Mags = MagazineCache::new(Cache::new(OBJ, 8), StaticPageProvider::new());
+    let mags = &MAGS;
+    mags.set_magazine_size(4);
+    mags.set_depot_limit(2);
+
+    // Le consommateur remplit ses magazines, le dépôt les rend au producteur.
+    common::producer_allocates_consumer_frees(OBJ, || mags.alloc_on(0), |p| unsafe { mags.free_on(1, p) });
+
+    assert_eq!(mags.live_objects(), 0);
+    assert!(mags.depot_full_magazines() <= 2);
+    mags.shrink();
+    assert_eq!(mags.lock_provider().free_pages(), PAGES);
+}
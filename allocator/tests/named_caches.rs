@@ -0,0 +1,205 @@
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+use allocator::{AllocError, CacheError, CacheFlags, SlabAllocator};
+
+#[cfg(not(miri))]
+use allocator::page_provider::StaticPageProvider;
+
+#[cfg(miri)]
+use allocator::page_provider::TestPageProvider;
+
+#[cfg(not(miri))]
+type Prov = StaticPageProvider<64>;
+#[cfg(miri)]
+type Prov = TestPageProvider;
+
+#[test]
+fn create_alloc_free_destroy() {
+    let mut a = SlabAllocator::new(Prov::new());
+
+    let inode = a.create_cache("inode", 40, 8, CacheFlags::NONE).expect("create");
+    assert_eq!(a.cache_name(inode), Some("inode"));
+    assert_eq!(a.find_cache("inode"), Some(inode));
+    assert_eq!(a.named_cache(inode).unwrap().obj_size(), 40);
+
+    let p = a.cache_alloc(inode);
+    let q = a.cache_alloc(inode);
+    assert!(!p.is_null() && !q.is_null());
+    // Cache dédié : objets serrés à 40 bytes (la size class serait 64).
+    assert_eq!((q as usize).abs_diff(p as usize), 40);
+
+    assert_eq!(a.destroy_cache(inode), Err(CacheError::Busy { live: 2 }));
+
+    unsafe {
+        a.cache_free(inode, p);
+        a.cache_free(inode, q);
+    }
+    assert_eq!(a.destroy_cache(inode), Ok(()));
+
+    // Handle périmé après destruction.
+    assert!(a.cache_alloc(inode).is_null());
+    assert_eq!(a.destroy_cache(inode), Err(CacheError::StaleHandle));
+    assert_eq!(a.find_cache("inode"), None);
+}
+
+#[test]
+fn hwcache_align_flag() {
+    let mut a = SlabAllocator::new(Prov::new());
+
+    let h = a.create_cache("dentry", 24, 8, CacheFlags::HWCACHE_ALIGN).unwrap();
+    let p = a.cache_alloc(h);
+    let q = a.cache_alloc(h);
+    assert_eq!((p as usize) % 64, 0);
+    assert_eq!((q as usize).abs_diff(p as usize), 64);
+    unsafe {
+        a.cache_free(h, p);
+        a.cache_free(h, q);
+    }
+}
+
+#[test]
+fn invalid_requests_are_rejected() {
+    let mut a = SlabAllocator::new(Prov::new());
+
+    assert_eq!(a.create_cache("huge", 8000, 8, CacheFlags::NONE), Err(CacheError::InvalidLayout));
+    assert_eq!(a.create_cache("odd", 32, 24, CacheFlags::NONE), Err(CacheError::InvalidLayout));
+
+    let h = a.create_cache("dup", 32, 8, CacheFlags::NONE).unwrap();
+    assert_eq!(a.create_cache("dup", 64, 8, CacheFlags::NONE), Err(CacheError::DuplicateName));
+
+    const NAMES: [&str; 16] = [
+        "c0", "c1", "c2", "c3", "c4", "c5", "c6", "c7", "c8", "c9", "c10", "c11", "c12", "c13",
+        "c14", "c15",
+    ];
+    let mut created = 1;
+    let mut full = None;
+    for name in NAMES {
+        match a.create_cache(name, 16, 8, CacheFlags::NONE) {
+            Ok(_) => created += 1,
+            Err(e) => {
+                full = Some(e);
+                break;
+            }
+        }
+    }
+    assert_eq!(created, allocator::named_cache::MAX_NAMED_CACHES);
+    assert_eq!(full, Some(CacheError::NoFreeSlot));
+
+    // Détruire libère un emplacement réutilisable.
+    a.destroy_cache(h).unwrap();
+    assert!(a.create_cache("again", 16, 8, CacheFlags::NONE).is_ok());
+}
+
+#[cfg(not(miri))]
+#[test]
+fn named_cache_pages_are_released_on_drop() {
+    let mut provider = StaticPageProvider::<16>::new();
+    {
+        let mut a = SlabAllocator::new(&mut provider);
+        let h = a.create_cache("obj", 512, 8, CacheFlags::NONE).unwrap();
+        for _ in 0..20 {
+            assert!(!a.cache_alloc(h).is_null());
+        }
+    }
+    assert_eq!(provider.free_pages(), 16);
+}
+
+#[test]
+fn constructed_objects_are_preserved() {
+    use core::ptr::NonNull;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    static DTORS: AtomicUsize = AtomicUsize::new(0);
+
+    fn ctor(obj: NonNull<u8>) {
+        unsafe { obj.as_ptr().write_bytes(0xAB, 48) };
+    }
+    fn dtor(_: NonNull<u8>) {
+        DTORS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let mut a = SlabAllocator::new(Prov::new());
+    let h = a
+        .create_cache_with("buf", 48, 8, CacheFlags::NONE, Some(ctor), Some(dtor))
+        .expect("create");
+
+    let p = a.cache_alloc(h);
+    assert!(!p.is_null());
+    let bytes = unsafe { core::slice::from_raw_parts(p, 48) };
+    assert!(bytes.iter().all(|&b| b == 0xAB));
+
+    unsafe { a.cache_free(h, p) };
+    let q = a.cache_alloc(h);
+    assert_eq!(q, p);
+    let bytes = unsafe { core::slice::from_raw_parts(q, 48) };
+    assert!(bytes.iter().all(|&b| b == 0xAB), "freelist node overwrote the object");
+
+    unsafe { a.cache_free(h, q) };
+    assert_eq!(a.destroy_cache(h), Ok(()));
+    assert!(DTORS.load(Ordering::Relaxed) > 0);
+}
+
+#[test]
+fn named_and_size_class_caches_of_same_size_stay_apart() {
+    let mut a = SlabAllocator::new(Prov::new());
+    let h = a.create_cache("obj64", 64, 8, CacheFlags::NONE).expect("create");
+    let layout = Layout::from_size_align(64, 8).unwrap();
+
+    // Objet du cache nommé rendu à la classe 64 (même pas) : refusé.
+    let p = a.cache_alloc(h);
+    assert!(!p.is_null());
+    let nn = NonNull::new(p).unwrap();
+    assert_eq!(unsafe { a.try_dealloc(nn, layout) }, Err(AllocError::WrongCache));
+    assert_eq!(unsafe { a.try_free(nn) }, Err(AllocError::WrongCache));
+    assert_eq!(a.named_cache(h).unwrap().live_objects(), 1);
+
+    unsafe { a.cache_free(h, p) };
+    assert_eq!(a.destroy_cache(h), Ok(()));
+}
+
+#[test]
+fn random_freelist_flag() {
+    use allocator::slab::FreelistRandom;
+
+    // Sans générateur configuré, le flag est refusé.
+    let mut a = SlabAllocator::new(Prov::new());
+    assert_eq!(
+        a.create_cache("rnd", 64, 8, CacheFlags::RANDOM_FREELIST),
+        Err(CacheError::NoRandomSource)
+    );
+
+    const OBJS: usize = 32;
+    let mut orders = [[0usize; OBJS]; 2];
+    for order in orders.iter_mut() {
+        let mut a = SlabAllocator::new(Prov::new()).with_random_freelist(FreelistRandom::with_seed(0x5EED));
+        let h = a.create_cache("rnd", 64, 8, CacheFlags::RANDOM_FREELIST).expect("create");
+        let plain = a.create_cache("plain", 64, 8, CacheFlags::NONE).expect("create");
+
+        let mut ptrs = [core::ptr::null_mut::<u8>(); OBJS];
+        for (p, slot) in ptrs.iter_mut().zip(order.iter_mut()) {
+            *p = a.cache_alloc(h);
+            assert!(!p.is_null());
+            *slot = *p as usize % 4096;
+        }
+        let mut sorted = ptrs;
+        sorted.sort_unstable();
+        assert!(sorted.windows(2).all(|w| w[0] != w[1]));
+
+        // Le cache sans le flag garde l'ordre des adresses.
+        let p = a.cache_alloc(plain);
+        let q = a.cache_alloc(plain);
+        assert_eq!(q as usize - p as usize, 64);
+
+        unsafe {
+            for p in ptrs {
+                a.cache_free(h, p);
+            }
+            a.cache_free(plain, p);
+            a.cache_free(plain, q);
+        }
+    }
+    // Même graine : même ordre, mélangé.
+    assert_eq!(orders[0], orders[1]);
+    assert!(!orders[0].windows(2).all(|w| w[0] < w[1]), "ordre non mélangé");
+}
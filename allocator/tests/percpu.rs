@@ -0,0 +1,177 @@
+//! Stress multi-thread de `PerCpuSlabAllocator`, libérations croisées comprises.
+
+mod common;
+
+use core::alloc::Layout;
+use std::sync::mpsc;
+use std::thread;
+
+use allocator::page_provider::StaticPageProvider;
+use allocator::PerCpuSlabAllocator;
+use common::{next, seed, thread_index, ROUNDS, THREADS};
+
+const PAGES: usize = 512;
+const CPUS: usize = 4;
+
+type Slab = PerCpuSlabAllocator<StaticPageProvider<PAGES>, CPUS>;
+type Sender = mpsc::SyncSender<(usize, Layout)>;
+type Receiver = mpsc::Receiver<(usize, Layout)>;
+
+/// Alloue au hasard ; une partie des blocs est libérée par le thread voisin.
+fn churn(slab: &Slab, id: usize, give: Sender, take: Receiver) {
+    const SIZES: [usize; 8] = [8, 16, 24, 48, 100, 256, 640, 5000];
+
+    let mut rng = seed(id);
+    let mut live: [Option<(*mut u8, Layout)>; 64] = [None; 64];
+
+    for _ in 0..ROUNDS {
+        let r = next(&mut rng);
+
+        // Blocs reçus du voisin : libérés ici, donc depuis un autre CPU.
+        while let Ok((p, layout)) = take.try_recv() {
+            unsafe { slab.dealloc(p as *mut u8, layout) };
+        }
+
+        let slot = &mut live[(r % 64) as usize];
+        match slot.take() {
+            Some((p, layout)) => {
+                let bytes = unsafe { core::slice::from_raw_parts(p, layout.size()) };
+                assert!(bytes.iter().all(|&b| b == id as u8), "thread {id}: corruption");
+                // Canal plein (voisin pas encore démarré) : libération locale.
+                if r & 0x100 == 0 || give.try_send((p as usize, layout)).is_err() {
+                    unsafe { slab.dealloc(p, layout) };
+                }
+            }
+            None => {
+                let size = SIZES[((r >> 16) % SIZES.len() as u64) as usize];
+                let layout = Layout::from_size_align(size, 8).unwrap();
+                let p = slab.alloc(layout);
+                assert!(!p.is_null(), "thread {id}: OOM");
+                unsafe { p.write_bytes(id as u8, size) };
+                *slot = Some((p, layout));
+            }
+        }
+    }
+
+    for (p, layout) in live.into_iter().flatten() {
+        if give.try_send((p as usize, layout)).is_err() {
+            unsafe { slab.dealloc(p, layout) };
+        }
+    }
+    drop(give);
+    for (p, layout) in take {
+        unsafe { slab.dealloc(p as *mut u8, layout) };
+    }
+}
+
+#[test]
+fn threads_free_each_others_objects() {
+    static SLAB: Slab = PerCpuSlabAllocator::new(StaticPageProvider::new()).with_cpu_id(thread_index);
+
+    // Anneau : le thread i envoie au thread i + 1.
+    let (senders, receivers): (Vec<_>, Vec<_>) = (0..THREADS).map(|_| mpsc::sync_channel(64)).unzip();
+    let mut receivers: Vec<_> = receivers.into_iter().map(Some).collect();
+
+    thread::scope(|s| {
+        for (id, give) in senders.into_iter().enumerate() {
+            let take = receivers[(id + THREADS - 1) % THREADS].take().unwrap();
+            s.spawn(move || churn(&SLAB, id, give, take));
+        }
+    });
+
+    assert_eq!(SLAB.live_objects(), 0);
+    SLAB.shrink();
+    assert_eq!(SLAB.shared().lock_provider().free_pages(), PAGES);
+}
+
+#[test]
+fn lock_free_slabs_take_each_others_objects() {
+    static SLAB: Slab = PerCpuSlabAllocator::new(StaticPageProvider::new())
+        .with_cpu_id(thread_index)
+        .with_lock_free_freelist();
+
+    // Même anneau : les libérations distantes arrivent dans la freelist sans
+    // verrou des slabs actifs pendant que leurs propriétaires y allouent.
+    let (senders, receivers): (Vec<_>, Vec<_>) = (0..THREADS).map(|_| mpsc::sync_channel(64)).unzip();
+    let mut receivers: Vec<_> = receivers.into_iter().map(Some).collect();
+
+    thread::scope(|s| {
+        for (id, give) in senders.into_iter().enumerate() {
+            let take = receivers[(id + THREADS - 1) % THREADS].take().unwrap();
+            s.spawn(move || churn(&SLAB, id, give, take));
+        }
+    });
+
+    assert_eq!(SLAB.live_objects(), 0);
+    SLAB.shrink();
+    assert_eq!(SLAB.shared().lock_provider().free_pages(), PAGES);
+}
+
+#[test]
+fn producer_allocates_consumer_frees() {
+    static SLAB: Slab = PerCpuSlabAllocator::new(StaticPageProvider::new());
+    let layout = Layout::from_size_align(48, 8).unwrap();
+
+    // Toutes les libérations sont distantes : le producteur (CPU 0) ne
+    // récupère ses objets que par la pile `remote` de ses slabs actifs.
+    common::producer_allocates_consumer_frees(
+        layout.size(),
+        || SLAB.alloc_on(0, layout),
+        |p| unsafe { SLAB.dealloc_on(1, p, layout) },
+    );
+
+    assert_eq!(SLAB.live_objects(), 0);
+    SLAB.shrink();
+    assert_eq!(SLAB.shared().lock_provider().free_pages(), PAGES);
+}
+
+#[test]
+fn flush_hands_active_slabs_back_to_the_shared_cache() {
+    let slab: PerCpuSlabAllocator<StaticPageProvider<8>, 2> =
+        PerCpuSlabAllocator::new(StaticPageProvider::new());
+    let layout = Layout::from_size_align(64, 8).unwrap();
+
+    let p = slab.alloc_on(0, layout);
+    assert!(!p.is_null());
+    assert_eq!(slab.shared().live_objects(), 0, "slab actif hors du cache partagé");
+
+    slab.flush(0);
+    assert_eq!(slab.shared().live_objects(), 1);
+
+    // Le CPU 1 reprend le même slab (partiel) au lieu d'une page neuve.
+    let q = slab.alloc_on(1, layout);
+    assert_eq!(p as usize & !4095, q as usize & !4095);
+
+    unsafe {
+        slab.dealloc_on(1, p, layout);
+        slab.dealloc_on(0, q, layout);
+    }
+    assert_eq!(slab.live_objects(), 0);
+    slab.shrink();
+    assert_eq!(slab.shared().lock_provider().free_pages(), 8);
+}
+
+#[test]
+fn owns_sees_objects_of_active_slabs() {
+    let slab: PerCpuSlabAllocator<StaticPageProvider<8>, 2> =
+        PerCpuSlabAllocator::new(StaticPageProvider::new());
+    let layout = Layout::from_size_align(64, 8).unwrap();
+    let big = Layout::from_size_align(5000, 8).unwrap();
+
+    // Servi par le slab gelé du CPU 0 : dans aucune liste du cache partagé.
+    let p = slab.alloc_on(0, layout);
+    let q = slab.alloc_on(1, big);
+    assert!(slab.owns(p) && slab.owns(p.wrapping_add(63)) && slab.owns(q));
+    let local = 0u64;
+    assert!(!slab.owns(&local as *const u64 as *const u8));
+
+    slab.flush(0);
+    assert!(slab.owns(p));
+
+    unsafe {
+        slab.dealloc_on(0, p, layout);
+        slab.dealloc_on(1, q, big);
+    }
+    slab.shrink();
+    assert!(!slab.owns(p));
+}